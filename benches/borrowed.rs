@@ -0,0 +1,116 @@
+//! Compares reading a large character into the owned and borrowed flat models
+//!
+//! The borrowed model only types a few fields of each property, so it is also compared with an owned read of the same shape:
+//! the same fields as `String`s, beside the raw json of each property.
+//! Run with `cargo bench --bench borrowed`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use dicecloud_models::data_models::borrowed_model::BorrowedFlatCharacter;
+use dicecloud_models::FlatCharacter;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+const CHARACTER: &str = include_str!("../tests/data/character.json");
+const COPIES: usize = 500;
+const RUNS: u32 = 20;
+
+/// The owned counterpart of the borrowed model, reading the same fields the same way
+#[derive(Deserialize)]
+#[serde(rename_all="camelCase")]
+#[allow(dead_code)]
+struct SameShapeCharacter{
+    creatures: Vec<Box<RawValue>>,
+    creature_properties: Vec<OwnedView<SameShapeProp>>,
+    creature_variables: Vec<Box<RawValue>>
+}
+#[allow(dead_code)]
+struct OwnedView<T>{
+    fields: T,
+    raw: Box<RawValue>
+}
+impl<'de,T: serde::de::DeserializeOwned> Deserialize<'de> for OwnedView<T>{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        let fields = serde_json::from_str(raw.get()).map_err(serde::de::Error::custom)?;
+        Ok(Self{fields, raw})
+    }
+}
+#[derive(Deserialize)]
+#[serde(rename_all="camelCase")]
+#[allow(dead_code)]
+struct SameShapeProp{
+    #[serde(rename="_id")]
+    id: String,
+    #[serde(rename="type")]
+    typ: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    variable_name: Option<String>,
+    tags: Vec<String>,
+    order: usize,
+    parent: SameShapeIdentifier,
+    ancestors: Vec<SameShapeIdentifier>,
+    #[serde(default)]
+    icon: Option<OwnedView<SameShapeIcon>>,
+    #[serde(default)]
+    library_tags: Vec<String>,
+    #[serde(default)]
+    inactive: bool,
+    #[serde(default)]
+    removed: bool
+}
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct SameShapeIdentifier{
+    id: String,
+    collection: String
+}
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct SameShapeIcon{
+    name: String,
+    shape: String
+}
+/// The test character with its properties repeated, to look like a large export
+fn large_character()->String{
+    let mut character: Value = serde_json::from_str(CHARACTER).expect("test character is valid json");
+    let props = character["creatureProperties"].as_array().cloned().unwrap_or_default();
+    let mut copies = Vec::with_capacity(props.len()*COPIES);
+    for i in 0..COPIES{
+        for prop in &props{
+            let mut prop = prop.clone();
+            prop["_id"] = Value::String(format!("{}{i}",prop["_id"].as_str().unwrap_or_default()));
+            copies.push(prop);
+        }
+    }
+    character["creatureProperties"] = Value::Array(copies);
+    character.to_string()
+}
+fn time(name: &str, mut run: impl FnMut())->Duration{
+    run();
+    let start = Instant::now();
+    for _ in 0..RUNS{
+        run();
+    }
+    let each = start.elapsed()/RUNS;
+    println!("{name:>10}: {each:?} per read");
+    each
+}
+fn main(){
+    let json = large_character();
+    println!("reading a {} KiB character {RUNS} times",json.len()/1024);
+    let owned = time("owned",||{
+        black_box(FlatCharacter::from_json(black_box(&json)).expect("owned read"));
+    });
+    let same_shape = time("same shape",||{
+        black_box(serde_json::from_str::<SameShapeCharacter>(black_box(&json)).expect("same shape read"));
+    });
+    let borrowed = time("borrowed",||{
+        black_box(BorrowedFlatCharacter::from_json(black_box(&json)).expect("borrowed read"));
+    });
+    println!("borrowed takes {:.0}% of the owned time, and {:.0}% of the same shape owned time",
+        100.0*borrowed.as_secs_f64()/owned.as_secs_f64(),100.0*borrowed.as_secs_f64()/same_shape.as_secs_f64());
+}
@@ -0,0 +1,96 @@
+use crate::calculation::eval::{Evaluator, Scope};
+use crate::data_models::generic_model::{ParseError, ParseNode, PropVal};
+
+/// The most dice a single roll node may roll, to keep typos like `1000000d6` from hanging
+pub const MAX_DICE: u64 = 10_000;
+/// The most sides a die may have, which together with [`MAX_DICE`] keeps the total of a roll from overflowing
+pub const MAX_SIDES: u64 = 1_000_000;
+
+/// A source of randomness for rolling dice
+///
+/// Any `FnMut(u64)->u64` closure can be used as one, which makes it easy to script rolls in tests
+pub trait DiceRng{
+    /// Roll one die with `sides` faces, returning a value from 1 to `sides` inclusive
+    fn roll_die(&mut self, sides: u64)->u64;
+}
+impl<F: FnMut(u64)->u64> DiceRng for F{
+    fn roll_die(&mut self, sides: u64)->u64 {
+        self(sides)
+    }
+}
+/// A small seedable generator (splitmix64), so the same seed always gives the same rolls
+///
+/// # Examples
+/// ```
+/// use dicecloud_models::calculation::dice::{DiceRng, SeededRng};
+/// let mut first = SeededRng::new(42);
+/// let mut second = SeededRng::new(42);
+/// let rolls: Vec<u64> = (0..5).map(|_|first.roll_die(20)).collect();
+/// assert_eq!(rolls, (0..5).map(|_|second.roll_die(20)).collect::<Vec<u64>>());
+/// assert!(rolls.iter().all(|r|(1..=20).contains(r)));
+/// ```
+#[derive(PartialEq,Eq,Debug,Clone)]
+pub struct SeededRng{
+    state: u64
+}
+impl SeededRng{
+    /// Create a generator from a seed
+    #[must_use]
+    pub const fn new(seed: u64)->Self{
+        Self{state: seed}
+    }
+    /// Get the next raw 64 bit value
+    pub const fn next_u64(&mut self)->u64{
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+impl DiceRng for SeededRng{
+    #[allow(clippy::cast_possible_truncation)]
+    fn roll_die(&mut self, sides: u64)->u64 {
+        // scale into range with a widening multiply rather than `%`, which avoids most of the bias
+        ((u128::from(self.next_u64()) * u128::from(sides)) >> 64) as u64 + 1
+    }
+}
+/// The outcome of rolling a single `XdY` node
+#[derive(PartialEq,Eq,Debug,Default,Clone,Hash)]
+pub struct DiceRoll{
+    /// How many dice were rolled
+    pub number: u64,
+    /// How many sides each die had
+    pub sides: u64,
+    /// The result of each die, in the order they were rolled
+    pub results: Vec<u64>,
+    pub total: u64,
+}
+/// Everything produced by evaluating a calculation with dice
+#[derive(PartialEq,Debug,Default,Clone)]
+pub struct RollOutcome{
+    pub value: PropVal,
+    /// Every roll node evaluated, in evaluation order
+    pub rolls: Vec<DiceRoll>,
+    pub errors: Vec<ParseError>,
+}
+impl ParseNode{
+    /// Evaluate this node, rolling any dice with `rng`
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use dicecloud_models::{CharacterVar, ParseNode, PropVal};
+    /// let num = |n|Box::new(ParseNode::Constant{value_type: "number".to_string(), value: PropVal::Number(n)});
+    /// let node = ParseNode::Roll{left: num(2), right: num(6)};
+    /// let vars: HashMap<String,CharacterVar> = HashMap::new();
+    /// let outcome = node.roll(&vars, &mut |_sides|4);
+    /// assert_eq!(outcome.value, PropVal::Number(8));
+    /// assert_eq!(outcome.rolls[0].results, vec![4, 4]);
+    /// ```
+    pub fn roll<S: Scope + ?Sized>(&self, scope: &S, rng: &mut dyn DiceRng)->RollOutcome{
+        let mut evaluator = Evaluator::new(scope).with_rng(rng);
+        let value = evaluator.evaluate(self);
+        RollOutcome{value, rolls: evaluator.take_rolls(), errors: evaluator.take_errors()}
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::calculation::dice::{DiceRng, DiceRoll, MAX_DICE, MAX_SIDES};
+use crate::data_models::generic_model::{Calculation, CharacterVar, GenericVariable, ParseError, ParseNode, PropVal, VariableType};
+use crate::data_models::flat_model::FlatCharacter;
+use crate::data_models::tree_model::TreeCharacter;
+
+/// How deep constants are allowed to refer to other constants before we give up
+const MAX_DEPTH: usize = 64;
+
+/// Something that variable names can be resolved against while evaluating a calculation
+pub trait Scope{
+    /// Get the variable with the given name, if there is one
+    fn variable(&self, name: &str)->Option<&CharacterVar>;
+}
+impl<S: BuildHasher> Scope for HashMap<String,CharacterVar,S>{
+    fn variable(&self, name: &str)->Option<&CharacterVar> {
+        self.get(name)
+    }
+}
+impl<S: BuildHasher> Scope for [HashMap<String,CharacterVar,S>]{
+    fn variable(&self, name: &str)->Option<&CharacterVar> {
+        self.iter().find_map(|vars|vars.get(name))
+    }
+}
+impl Scope for FlatCharacter{
+    fn variable(&self, name: &str)->Option<&CharacterVar> {
+        self.creature_variables.as_slice().variable(name)
+    }
+}
+impl Scope for TreeCharacter{
+    fn variable(&self, name: &str)->Option<&CharacterVar> {
+        self.creature_variables.as_slice().variable(name)
+    }
+}
+/// An intermediate result, arrays can't be stored in a `PropVal` so they are kept seperate
+enum Value{
+    Single(PropVal),
+    Array(Vec<PropVal>)
+}
+/// Evaluates `ParseNode` trees against a set of variables
+///
+/// Mirrors dicecloud: missing variables are treated as 0, and anything that goes wrong is recorded as a `ParseError` rather than stopping evaluation
+///
+/// Rolls are only evaluated if a [`DiceRng`] is provided with [`Evaluator::with_rng`]
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use dicecloud_models::{CharacterVar, ParseNode, PropVal};
+/// use dicecloud_models::calculation::eval::Evaluator;
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("level".to_string(), CharacterVar::Empty{v: Some(3).into(), extra: Default::default()});
+/// let node = ParseNode::Operator{
+///     left: Box::new(ParseNode::Symbol{name: "level".to_string()}),
+///     right: Box::new(ParseNode::Constant{value_type: "number".to_string(), value: PropVal::Number(2)}),
+///     operator: "*".to_string(), fun: "multiply".to_string()};
+/// let mut evaluator = Evaluator::new(&vars);
+/// assert_eq!(evaluator.evaluate(&node), PropVal::Number(6));
+/// assert!(evaluator.errors().is_empty());
+/// ```
+pub struct Evaluator<'a, S: ?Sized>{
+    scope: &'a S,
+    rng: Option<&'a mut dyn DiceRng>,
+    errors: Vec<ParseError>,
+    rolls: Vec<DiceRoll>,
+    depth: usize,
+}
+impl<'a, S: Scope + ?Sized> Evaluator<'a, S>{
+    /// Create an evaluator that looks variables up in `scope`
+    #[must_use]
+    pub const fn new(scope: &'a S)->Self{
+        Self{scope, rng: None, errors: Vec::new(), rolls: Vec::new(), depth: 0}
+    }
+    /// Use `rng` to roll any dice encountered
+    #[must_use]
+    pub fn with_rng(mut self, rng: &'a mut dyn DiceRng)->Self{
+        self.rng = Some(rng);
+        self
+    }
+    /// The errors collected so far
+    #[must_use]
+    pub fn errors(&self)->&[ParseError]{
+        &self.errors
+    }
+    /// Takes the errors collected so far, leaving none behind
+    pub fn take_errors(&mut self)->Vec<ParseError>{
+        std::mem::take(&mut self.errors)
+    }
+    /// The dice rolled so far, in the order they were rolled
+    #[must_use]
+    pub fn rolls(&self)->&[DiceRoll]{
+        &self.rolls
+    }
+    /// Takes the dice rolled so far, leaving none behind
+    pub fn take_rolls(&mut self)->Vec<DiceRoll>{
+        std::mem::take(&mut self.rolls)
+    }
+    /// Evaluate a node down to a single value
+    pub fn evaluate(&mut self, node: &ParseNode)->PropVal{
+        match self.eval(node){
+            Value::Single(v)=>v,
+            Value::Array(_)=>self.fail("An array can't be reduced to a single value")
+        }
+    }
+    fn eval(&mut self, node: &ParseNode)->Value{
+        match node{
+            ParseNode::Array{values}=>Value::Array(values.iter().map(|v|self.evaluate(v)).collect()),
+            ParseNode::Parenthesis{content}=>self.eval(content),
+            _=>Value::Single(self.eval_single(node))
+        }
+    }
+    fn eval_single(&mut self, node: &ParseNode)->PropVal{
+        match node{
+            ParseNode::Accessor{path,name}=>self.access(name,path),
+            ParseNode::Array{..}=>self.fail("An array can't be reduced to a single value"),
+            ParseNode::Call{function_name,args}=>self.call(function_name,args),
+            ParseNode::Constant{value,..}=>value.clone(),
+            ParseNode::Error{error}=>{
+                self.errors.push(error.clone());
+                PropVal::default()
+            },
+            ParseNode::If{condition,consequent,alternative}=>{
+                if truthy(&self.evaluate(condition)){
+                    self.evaluate(consequent)
+                } else {
+                    self.evaluate(alternative)
+                }
+            },
+            ParseNode::Index{array,index}=>self.index(array,index),
+            ParseNode::Not{right}=>PropVal::Boolean(!truthy(&self.evaluate(right))),
+            ParseNode::Operator{left,right,operator,..}=>self.operator(left,right,operator),
+            ParseNode::Parenthesis{content}=>self.evaluate(content),
+            ParseNode::Roll{left,right}=>self.roll(left,right),
+            ParseNode::Symbol{name}=>self.symbol(name),
+            ParseNode::UnaryOperator{operator,right}=>{
+                let val = self.evaluate(right);
+                match operator.as_str(){
+                    "-"=>self.number(&val).map_or_else(PropVal::default,|n|from_f64(-n)),
+                    "+"=>self.number(&val).map_or_else(PropVal::default,from_f64),
+                    op=>self.fail(&format!("Unknown unary operator {op}"))
+                }
+            }
+        }
+    }
+    fn fail(&mut self, message: &str)->PropVal{
+        self.errors.push(ParseError::new("error",message));
+        PropVal::default()
+    }
+    fn number(&mut self, val: &PropVal)->Option<f64>{
+        let num = match val{
+            PropVal::Boolean(b)=>Some(if *b {1.0} else {0.0}),
+            PropVal::None(_)=>Some(0.0),
+            PropVal::Number(_)|PropVal::Fraction(_)=>val.as_f64(),
+            PropVal::Str(s)=>s.trim().parse().ok()
+        };
+        if num.is_none(){
+            self.fail(&format!("{val} is not a number"));
+        }
+        num
+    }
+    fn symbol(&mut self, name: &str)->PropVal{
+        let scope = self.scope;
+        if let Some(var) = scope.variable(name){
+            self.variable_value(var)
+        } else {
+            self.errors.push(ParseError::new("info",&format!("{name} not found, set to 0")));
+            PropVal::default()
+        }
+    }
+    fn access(&mut self, name: &str, path: &[String])->PropVal{
+        let scope = self.scope;
+        let Some(var) = scope.variable(name) else {
+            return self.symbol(name);
+        };
+        match path{
+            []=>self.variable_value(var),
+            [field]=>self.variable_field(var,field).unwrap_or_else(||{
+                self.errors.push(ParseError::new("info",&format!("{name}.{field} not found, set to 0")));
+                PropVal::default()
+            }),
+            _=>self.fail(&format!("Can't access {name}.{}",path.join(".")))
+        }
+    }
+    fn variable_value(&mut self, var: &CharacterVar)->PropVal{
+        match var{
+            CharacterVar::Var(v)=>self.variable_field(var,"value").unwrap_or_else(||{
+                self.errors.push(ParseError::new("info",&format!("{} has no value, set to 0",v.variable_name)));
+                PropVal::default()
+            }),
+            CharacterVar::Str(s)=>PropVal::Str(s.clone()),
+            CharacterVar::Empty{v,..}=>PropVal::Number(v.unwrap_or_default())
+        }
+    }
+    fn variable_field(&mut self, var: &CharacterVar, field: &str)->Option<PropVal>{
+        let CharacterVar::Var(GenericVariable{variable_name,name,var_type,..}) = var else {
+            return None;
+        };
+        let calc_value = |c: &Option<Calculation>|c.as_ref().map(|c|c.value.clone());
+        match (field,var_type){
+            ("name",_)=>Some(PropVal::Str(name.to_string())),
+            ("variableName",_)=>Some(PropVal::Str(variable_name.clone())),
+            ("value",VariableType::Attribute{value,..})=>Some((**value).clone()),
+            ("total",VariableType::Attribute{total,..})=>Some((**total).clone()),
+            ("damage",VariableType::Attribute{damage,..})=>damage.map(PropVal::Number),
+            ("modifier",VariableType::Attribute{modifier,..})=>modifier.map(PropVal::Number),
+            ("proficiency",VariableType::Attribute{proficiency,..})=>proficiency.map(from_f64),
+            ("baseValue",VariableType::Attribute{base_value,..}|VariableType::Skill{base_value,..})=>calc_value(base_value),
+            ("decimal",VariableType::Attribute{decimal,..})=>Some(PropVal::Boolean(**decimal)),
+            ("hide",VariableType::Attribute{hide,..}|VariableType::Skill{hide,..})=>Some(PropVal::Boolean(**hide)),
+            ("overridden",VariableType::Attribute{overridden,..}|VariableType::Skill{overridden,..})=>Some(PropVal::Boolean(**overridden)),
+            ("value",VariableType::Skill{value,..})=>Some(PropVal::Number(*value)),
+            ("abilityMod",VariableType::Skill{ability_mod,..})=>Some(PropVal::Number(**ability_mod)),
+            ("advantage",VariableType::Skill{advantage,..})=>Some(PropVal::Number(**advantage)),
+            ("passiveBonus",VariableType::Skill{passive_bonus,..})=>Some(PropVal::Number(**passive_bonus)),
+            ("proficiency",VariableType::Skill{proficiency,..})=>Some(from_f64(*proficiency)),
+            ("fail",VariableType::Skill{fail,..})=>Some(PropVal::Number(**fail)),
+            ("ability",VariableType::Skill{ability,..})=>(**ability).clone().map(PropVal::Str),
+            ("value",VariableType::Constant{value,..})=>{
+                if self.depth>=MAX_DEPTH{
+                    return Some(self.fail(&format!("{variable_name} refers to itself too deeply")));
+                }
+                self.depth+=1;
+                let val = self.evaluate(value);
+                self.depth-=1;
+                Some(val)
+            },
+            ("value"|"level",VariableType::Class{level,..})=>Some(PropVal::Number(**level)),
+            ("value",VariableType::Toggle{disabled,enabled,condition,..})=>Some(PropVal::Boolean(
+                **disabled!=Some(true) && (**enabled==Some(true) || condition.as_ref().is_some_and(|c|truthy(&c.value))))),
+            ("enabled",VariableType::Toggle{enabled,..})=>Some(PropVal::Boolean(**enabled==Some(true))),
+            ("disabled",VariableType::Toggle{disabled,..})=>Some(PropVal::Boolean(**disabled==Some(true))),
+            _=>None
+        }
+    }
+    fn operator(&mut self, left: &ParseNode, right: &ParseNode, operator: &str)->PropVal{
+        let lhs = self.evaluate(left);
+        match operator{
+            "&&"=>return if truthy(&lhs) {self.evaluate(right)} else {lhs},
+            "||"=>return if truthy(&lhs) {lhs} else {self.evaluate(right)},
+            _=>{}
+        }
+        let rhs = self.evaluate(right);
+        match operator{
+            "+" if matches!(lhs,PropVal::Str(_))||matches!(rhs,PropVal::Str(_))=>PropVal::Str(format!("{lhs}{rhs}")),
+            "=="|"==="=>PropVal::Boolean(loose_eq(&lhs,&rhs)),
+            "!="|"!=="=>PropVal::Boolean(!loose_eq(&lhs,&rhs)),
+            "+"|"-"|"*"|"/"|"%"|"^"|">"|"<"|">="|"<="=>{
+                let (Some(a),Some(b)) = (self.number(&lhs),self.number(&rhs)) else {
+                    return PropVal::default();
+                };
+                match operator{
+                    "+"=>from_f64(a+b),
+                    "-"=>from_f64(a-b),
+                    "*"=>from_f64(a*b),
+                    "/"=>from_f64(a/b),
+                    "%"=>from_f64(a%b),
+                    "^"=>from_f64(a.powf(b)),
+                    ">"=>PropVal::Boolean(a>b),
+                    "<"=>PropVal::Boolean(a<b),
+                    ">="=>PropVal::Boolean(a>=b),
+                    _=>PropVal::Boolean(a<=b),
+                }
+            },
+            op=>self.fail(&format!("Unknown operator {op}"))
+        }
+    }
+    fn roll(&mut self, left: &ParseNode, right: &ParseNode)->PropVal{
+        let (number,sides) = (self.evaluate(left),self.evaluate(right));
+        let (Some(number),Some(sides)) = (self.count(&number),self.count(&sides)) else {
+            return PropVal::default();
+        };
+        if number>MAX_DICE{
+            return self.fail(&format!("Can't roll more than {MAX_DICE} dice at once"));
+        }
+        if sides==0{
+            return self.fail("Can't roll a die with no sides");
+        }
+        if sides>MAX_SIDES{
+            return self.fail(&format!("Can't roll a die with more than {MAX_SIDES} sides"));
+        }
+        let Some(rng) = self.rng.as_mut() else {
+            return self.fail("Rolls can't be evaluated without dice");
+        };
+        let results: Vec<u64> = (0..number).map(|_|rng.roll_die(sides)).collect();
+        // a scripted rng can return more than `sides`, so don't trust the caps alone
+        let total = results.iter().fold(0,|total: u64,&result|total.saturating_add(result));
+        self.rolls.push(DiceRoll{number, sides, results, total});
+        PropVal::Number(i64::try_from(total).unwrap_or(i64::MAX))
+    }
+    /// A number that has to be a whole, non-negative count (like the number of dice)
+    fn count(&mut self, val: &PropVal)->Option<u64>{
+        let n = self.number(val)?;
+        if n<0.0 || n.fract()!=0.0{
+            self.fail(&format!("{val} is not a whole number of dice"));
+            return None;
+        }
+        #[allow(clippy::cast_possible_truncation,clippy::cast_sign_loss)]
+        Some(n as u64)
+    }
+    fn index(&mut self, array: &ParseNode, index: &ParseNode)->PropVal{
+        let Value::Array(values) = self.eval(array) else {
+            return self.fail("Only arrays can be indexed");
+        };
+        let idx = self.evaluate(index);
+        let Some(i) = self.number(&idx) else {
+            return PropVal::default();
+        };
+        // dicecloud arrays are indexed from 1
+        #[allow(clippy::cast_possible_truncation,clippy::cast_sign_loss)]
+        let pos = (i.trunc() as usize).checked_sub(1);
+        match pos.and_then(|p|values.get(p)){
+            Some(v) if i>=1.0=>v.clone(),
+            _=>self.fail(&format!("Index {idx} is out of range"))
+        }
+    }
+    fn call(&mut self, function_name: &str, args: &[Box<ParseNode>])->PropVal{
+        if function_name=="tableLookup"{
+            return self.table_lookup(args);
+        }
+        let mut nums = Vec::with_capacity(args.len());
+        for arg in args{
+            let val = self.evaluate(arg);
+            match self.number(&val){
+                Some(n)=>nums.push(n),
+                None=>return PropVal::default()
+            }
+        }
+        let unary = |f: fn(f64)->f64, this: &mut Self|match nums.as_slice(){
+            [n]=>from_f64(f(*n)),
+            _=>this.fail(&format!("{function_name} takes exactly 1 argument"))
+        };
+        match function_name{
+            "abs"=>unary(f64::abs,self),
+            "sqrt"=>unary(f64::sqrt,self),
+            "round"=>unary(f64::round,self),
+            "floor"=>unary(f64::floor,self),
+            "ceil"=>unary(f64::ceil,self),
+            "trunc"=>unary(f64::trunc,self),
+            "sign"=>unary(|n|if n==0.0 {0.0} else {n.signum()},self),
+            "max"=>nums.into_iter().reduce(f64::max).map_or_else(||self.fail("max needs at least 1 argument"),from_f64),
+            "min"=>nums.into_iter().reduce(f64::min).map_or_else(||self.fail("min needs at least 1 argument"),from_f64),
+            name=>self.fail(&format!("Unknown function {name}"))
+        }
+    }
+    /// The (1-based) index of the last entry in the table not exceeding the value
+    fn table_lookup(&mut self, args: &[Box<ParseNode>])->PropVal{
+        let [table,value] = args else {
+            return self.fail("tableLookup takes exactly 2 arguments");
+        };
+        let Value::Array(entries) = self.eval(table) else {
+            return self.fail("tableLookup expects an array as its first argument");
+        };
+        let val = self.evaluate(value);
+        let Some(target) = self.number(&val) else {
+            return PropVal::default();
+        };
+        let mut found = 0;
+        for (i,entry) in entries.iter().enumerate(){
+            match self.number(entry){
+                Some(n) if n<=target=>found = i+1,
+                _=>break
+            }
+        }
+        PropVal::Number(i64::try_from(found).unwrap_or(i64::MAX))
+    }
+}
+/// Whether a value counts as true in a condition
+fn truthy(val: &PropVal)->bool{
+    match val{
+        PropVal::Boolean(b)=>*b,
+        PropVal::None(_)=>false,
+        PropVal::Number(n)=>*n!=0,
+        PropVal::Fraction(f)=>*f!=0.0 && !f.is_nan(),
+        PropVal::Str(s)=>!s.is_empty()
+    }
+}
+/// Equality that treats `Number` and `Fraction` as the same kind of thing
+fn loose_eq(lhs: &PropVal, rhs: &PropVal)->bool{
+    match (lhs.as_f64(),rhs.as_f64()){
+        (Some(a),Some(b))=>(a-b).abs()<f64::EPSILON,
+        _=>lhs==rhs
+    }
+}
+/// Store whole numbers as `Number`, the same way they would come out of the json
+#[allow(clippy::cast_possible_truncation,clippy::cast_precision_loss)]
+fn from_f64(f: f64)->PropVal{
+    if f.fract()==0.0 && f.abs()<=i64::MAX as f64{
+        PropVal::Number(f as i64)
+    } else {
+        PropVal::Fraction(f)
+    }
+}
+impl ParseNode{
+    /// Evaluate this node against some variables, returning the value and any errors encountered
+    ///
+    /// See [`Evaluator`] for details
+    pub fn evaluate<S: Scope + ?Sized>(&self, scope: &S)->(PropVal,Vec<ParseError>){
+        let mut evaluator = Evaluator::new(scope);
+        let val = evaluator.evaluate(self);
+        (val,evaluator.take_errors())
+    }
+}
+impl Calculation{
+    /// Re-evaluate the parse node, replacing the cached `value` and `errors`
+    ///
+    /// Usefull after editing a character offline
+    pub fn recompute<S: Scope + ?Sized>(&mut self, scope: &S){
+        let (value,errors) = self.parse_node.evaluate(scope);
+        self.value = value;
+        self.errors = errors;
+    }
+}
@@ -0,0 +1,11 @@
+#![warn(clippy::pedantic,clippy::nursery,clippy::unwrap_used,clippy::perf)]
+//! Tools for working with the calculations dicecloud attaches to properties
+//!
+//! The [`Evaluator`][crate::calculation::eval::Evaluator] can recompute a [`ParseNode`][crate::ParseNode] offline,
+//! rolling any dice with a [`DiceRng`][crate::calculation::dice::DiceRng]
+//!
+//! `ParseNode` also implements `Display` and `FromStr`, so it can be printed back as formula text and parsed from it
+pub mod dice;
+pub mod eval;
+pub mod parser;
+pub mod print;
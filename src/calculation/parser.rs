@@ -0,0 +1,378 @@
+use std::str::FromStr;
+
+use crate::calculation::print::{binary_precedence, EXPONENT, ROLL};
+use crate::data_models::generic_model::{Calculation, ParseError, ParseNode, PropVal};
+
+/// Operators and punctuation, longest first so `===` wins over `==`
+const SYMBOLS: [&str; 25] = ["===","!==","==","!=",">=","<=","&&","||",">","<","+","-","*","/","%","^","!",
+    "?",":",",","(",")","[","]","."];
+
+#[derive(PartialEq,Debug,Clone)]
+enum Token{
+    Number(PropVal),
+    Str(String),
+    Name(String),
+    Roll,
+    Sym(&'static str),
+}
+/// Split a formula into tokens, each paired with the character it started at
+fn tokenize(input: &str)->Result<Vec<(Token,usize)>,ParseError>{
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i<chars.len(){
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace(){
+            i+=1;
+        } else if c.is_ascii_digit(){
+            while i<chars.len() && chars[i].is_ascii_digit(){
+                i+=1;
+            }
+            if i+1<chars.len() && chars[i]=='.' && chars[i+1].is_ascii_digit(){
+                i+=1;
+                while i<chars.len() && chars[i].is_ascii_digit(){
+                    i+=1;
+                }
+            }
+            tokens.push((number(&chars[start..i].iter().collect::<String>(),start)?,start));
+        } else if c.is_alphabetic()||c=='_'||c=='#'||c=='$'{
+            // the first character may be a `#` or `$`, which can't appear later in the name
+            i+=1;
+            while i<chars.len() && (chars[i].is_alphanumeric()||chars[i]=='_'){
+                i+=1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match word.as_str(){
+                "true"|"false"=>tokens.push((Token::Number(PropVal::Boolean(word=="true")),start)),
+                _=>if let Some(rolls) = rolls(&word){
+                    for (at,sides) in rolls{
+                        tokens.push((Token::Roll,start+at));
+                        if !sides.is_empty(){
+                            tokens.push((number(sides,start+at+1)?,start+at+1));
+                        }
+                    }
+                } else {
+                    tokens.push((Token::Name(word),start));
+                }
+            }
+        } else if c=='"'||c=='\''{
+            let mut text = String::new();
+            i+=1;
+            loop{
+                match chars.get(i){
+                    None=>return Err(error(&format!("Unterminated string starting at character {start}"))),
+                    Some(&q) if q==c=>break,
+                    Some('\\')=>{
+                        if let Some(&escaped) = chars.get(i+1){
+                            text.push(escaped);
+                        }
+                        i+=2;
+                    },
+                    Some(&other)=>{
+                        text.push(other);
+                        i+=1;
+                    }
+                }
+            }
+            i+=1;
+            tokens.push((Token::Str(text),start));
+        } else {
+            let rest: String = chars[i..].iter().take(3).collect();
+            let Some(sym) = SYMBOLS.iter().find(|s|rest.starts_with(*s)) else {
+                return Err(error(&format!("Unexpected character {c} at character {start}")));
+            };
+            i+=sym.chars().count();
+            tokens.push((Token::Sym(sym),start));
+        }
+    }
+    Ok(tokens)
+}
+/// Split a word like `d`, `d20` or `d6d4` into the position and sides of each roll, or `None` if it is a name
+///
+/// Only the last roll may leave out its sides, so `dd` is still a name
+fn rolls(word: &str)->Option<Vec<(usize,&str)>>{
+    let mut rolls = Vec::new();
+    let mut at = 0;
+    while at<word.len(){
+        let rest = word[at..].strip_prefix('d')?;
+        let sides = &rest[..rest.find(|c: char|!c.is_ascii_digit()).unwrap_or(rest.len())];
+        rolls.push((at,sides));
+        at+=1+sides.len();
+        if sides.is_empty() && at<word.len(){
+            return None;
+        }
+    }
+    Some(rolls)
+}
+fn number(text: &str, at: usize)->Result<Token,ParseError>{
+    text.parse::<i64>().map(PropVal::Number)
+        .or_else(|_|text.parse::<f64>().map(PropVal::Fraction))
+        .map(Token::Number)
+        .map_err(|_|error(&format!("Invalid number {text} at character {at}")))
+}
+fn error(message: &str)->ParseError{
+    ParseError::new("error",message)
+}
+/// The `fn` dicecloud records for each binary operator
+fn operator_fn(operator: &str)->&'static str{
+    match operator{
+        "||"=>"or",
+        "&&"=>"and",
+        "=="|"!="|"==="|"!=="=>"equality",
+        ">"|"<"|">="|"<="=>"relation",
+        "+"|"-"=>"add",
+        "%"=>"remainder",
+        "^"=>"exponent",
+        _=>"multiply"
+    }
+}
+fn constant(value: PropVal)->ParseNode{
+    let value_type = match value{
+        PropVal::Boolean(_)=>"boolean",
+        PropVal::Str(_)=>"string",
+        _=>"number"
+    };
+    ParseNode::Constant{value_type: value_type.to_string(), value}
+}
+/// How deep brackets, ternaries and prefix operators can nest before the formula is rejected,
+/// well short of where the recursion would run out of stack
+const MAX_DEPTH: usize = 128;
+/// A recursive descent parser over the tokens of a formula
+struct Parser{
+    tokens: Vec<(Token,usize)>,
+    pos: usize,
+    depth: usize,
+}
+impl Parser{
+    /// Run `f` one level deeper, failing if that is too deep
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self)->Result<T,ParseError>)->Result<T,ParseError>{
+        if self.depth>=MAX_DEPTH{
+            return Err(error("formula nested too deeply"));
+        }
+        self.depth+=1;
+        let result = f(self);
+        self.depth-=1;
+        result
+    }
+    fn peek(&self)->Option<&Token>{
+        self.tokens.get(self.pos).map(|(t,_)|t)
+    }
+    fn peek_sym(&self)->Option<&'static str>{
+        match self.peek(){
+            Some(Token::Sym(s))=>Some(s),
+            _=>None
+        }
+    }
+    fn next(&mut self)->Option<Token>{
+        let tok = self.tokens.get(self.pos).map(|(t,_)|t.clone());
+        self.pos+=1;
+        tok
+    }
+    fn unexpected(&self)->ParseError{
+        match self.tokens.get(self.pos){
+            Some((tok,at))=>error(&format!("Unexpected {} at character {at}",describe(tok))),
+            None=>error("Unexpected end of input")
+        }
+    }
+    fn expect(&mut self, sym: &str)->Result<(),ParseError>{
+        if self.peek_sym()==Some(sym){
+            self.pos+=1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+    fn expression(&mut self)->Result<ParseNode,ParseError>{
+        self.nested(Self::ternary)
+    }
+    fn ternary(&mut self)->Result<ParseNode,ParseError>{
+        let condition = self.binary(2)?;
+        if self.peek_sym()!=Some("?"){
+            return Ok(condition);
+        }
+        self.pos+=1;
+        let consequent = self.binary(2)?;
+        self.expect(":")?;
+        let alternative = self.expression()?;
+        Ok(ParseNode::If{condition: Box::new(condition),consequent: Box::new(consequent),alternative: Box::new(alternative)})
+    }
+    /// Binary operators binding at least as tightly as `min`, by precedence climbing
+    fn binary(&mut self, min: u8)->Result<ParseNode,ParseError>{
+        let mut left = self.roll()?;
+        while let Some(op) = self.peek_sym(){
+            match binary_precedence(op){
+                Some(prec) if prec>=min && prec<ROLL=>{
+                    self.pos+=1;
+                    let right = self.binary(prec+1)?;
+                    left = ParseNode::Operator{left: Box::new(left),right: Box::new(right),
+                        operator: op.to_string(),fun: operator_fn(op).to_string()};
+                },
+                _=>break
+            }
+        }
+        Ok(left)
+    }
+    fn roll(&mut self)->Result<ParseNode,ParseError>{
+        let mut left = if self.peek()==Some(&Token::Roll){
+            constant(PropVal::Number(1))
+        } else {
+            self.unary()?
+        };
+        while self.peek()==Some(&Token::Roll){
+            self.pos+=1;
+            let right = self.unary()?;
+            left = ParseNode::Roll{left: Box::new(left),right: Box::new(right)};
+        }
+        Ok(left)
+    }
+    fn unary(&mut self)->Result<ParseNode,ParseError>{
+        self.nested(Self::prefixed)
+    }
+    fn prefixed(&mut self)->Result<ParseNode,ParseError>{
+        match self.peek_sym(){
+            Some(op@("-"|"+"))=>{
+                self.pos+=1;
+                Ok(ParseNode::UnaryOperator{operator: op.to_string(),right: Box::new(self.unary()?)})
+            },
+            Some("!")=>{
+                self.pos+=1;
+                Ok(ParseNode::Not{right: Box::new(self.unary()?)})
+            },
+            _=>self.exponent()
+        }
+    }
+    fn exponent(&mut self)->Result<ParseNode,ParseError>{
+        let base = self.postfix()?;
+        if self.peek_sym().and_then(binary_precedence)!=Some(EXPONENT){
+            return Ok(base);
+        }
+        self.pos+=1;
+        let power = self.unary()?;
+        Ok(ParseNode::Operator{left: Box::new(base),right: Box::new(power),operator: "^".to_string(),fun: operator_fn("^").to_string()})
+    }
+    fn postfix(&mut self)->Result<ParseNode,ParseError>{
+        let mut node = self.primary()?;
+        while self.peek_sym()==Some("["){
+            self.pos+=1;
+            let index = self.expression()?;
+            self.expect("]")?;
+            node = ParseNode::Index{array: Box::new(node),index: Box::new(index)};
+        }
+        Ok(node)
+    }
+    /// A comma seperated list of expressions, ending with `close`
+    #[allow(clippy::vec_box)]
+    fn list(&mut self, close: &str)->Result<Vec<Box<ParseNode>>,ParseError>{
+        let mut items = Vec::new();
+        if self.peek_sym()==Some(close){
+            self.pos+=1;
+            return Ok(items);
+        }
+        loop{
+            items.push(Box::new(self.expression()?));
+            match self.peek_sym(){
+                Some(",")=>self.pos+=1,
+                Some(s) if s==close=>{
+                    self.pos+=1;
+                    return Ok(items);
+                },
+                _=>return Err(self.unexpected())
+            }
+        }
+    }
+    fn primary(&mut self)->Result<ParseNode,ParseError>{
+        let start = self.pos;
+        match self.next(){
+            Some(Token::Number(value))=>Ok(constant(value)),
+            Some(Token::Str(text))=>Ok(constant(PropVal::Str(text))),
+            Some(Token::Name(name))=>match self.peek_sym(){
+                Some("(")=>{
+                    self.pos+=1;
+                    Ok(ParseNode::Call{function_name: name,args: self.list(")")?})
+                },
+                Some(".")=>{
+                    let mut path = Vec::new();
+                    while self.peek_sym()==Some("."){
+                        self.pos+=1;
+                        if let Some(Token::Name(field)) = self.next(){
+                            path.push(field);
+                        } else {
+                            self.pos-=1;
+                            return Err(self.unexpected());
+                        }
+                    }
+                    Ok(ParseNode::Accessor{path,name})
+                },
+                _=>Ok(ParseNode::Symbol{name})
+            },
+            Some(Token::Sym("("))=>{
+                let content = self.expression()?;
+                self.expect(")")?;
+                Ok(ParseNode::Parenthesis{content: Box::new(content)})
+            },
+            Some(Token::Sym("["))=>Ok(ParseNode::Array{values: self.list("]")?}),
+            _=>{
+                self.pos = start;
+                Err(self.unexpected())
+            }
+        }
+    }
+}
+fn describe(tok: &Token)->String{
+    match tok{
+        Token::Number(v)=>v.to_string(),
+        Token::Str(s)=>format!("\"{s}\""),
+        Token::Name(n)=>n.clone(),
+        Token::Roll=>"d".to_string(),
+        Token::Sym(s)=>(*s).to_string()
+    }
+}
+/// Parse a formula into a `ParseNode`, or a `ParseError` describing what went wrong
+///
+/// # Errors
+/// If the formula is not valid dicecloud syntax
+pub fn parse(formula: &str)->Result<ParseNode,ParseError>{
+    let mut parser = Parser{tokens: tokenize(formula)?, pos: 0, depth: 0};
+    let node = parser.expression()?;
+    if parser.pos<parser.tokens.len(){
+        return Err(parser.unexpected());
+    }
+    Ok(node)
+}
+impl FromStr for ParseNode{
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+impl ParseNode{
+    /// Parse a formula the way dicecloud would, producing an `Error` node if it is invalid
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::{ParseNode, PropVal};
+    /// let node = ParseNode::parse("1d20 + (dne ? 1 : 0)");
+    /// assert_eq!(node.to_string(), "1d20 + (dne ? 1 : 0)");
+    /// assert!(matches!(ParseNode::parse("1 +"), ParseNode::Error{..}));
+    /// ```
+    #[must_use]
+    pub fn parse(formula: &str)->Self{
+        parse(formula).unwrap_or_else(|error|Self::Error{error})
+    }
+}
+impl Calculation{
+    /// Build a new calculation for the field `key` from formula text
+    ///
+    /// The value is left at its default until the calculation is evaluated
+    #[must_use]
+    pub fn from_formula(key: &str, formula: &str)->Self{
+        let parse_node = ParseNode::parse(formula);
+        let errors = match &parse_node{
+            ParseNode::Error{error}=>vec![error.clone()],
+            _=>Vec::new()
+        };
+        Self{calculation: formula.to_string(), key: key.to_string(), typ: "_calculation".to_string(),
+            parse_node, errors, ..Default::default()}
+    }
+}
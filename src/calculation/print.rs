@@ -0,0 +1,158 @@
+use core::fmt;
+
+use crate::data_models::generic_model::{Calculation, ParseNode, PropVal};
+
+// Binding strength of each kind of node, higher binds tighter
+pub(crate) const IF: u8 = 1;
+pub(crate) const ROLL: u8 = 9;
+pub(crate) const UNARY: u8 = 10;
+pub(crate) const EXPONENT: u8 = 11;
+pub(crate) const POSTFIX: u8 = 12;
+pub(crate) const ATOM: u8 = 13;
+
+/// The precedence of a binary operator, if it is one dicecloud understands
+pub(crate) fn binary_precedence(operator: &str)->Option<u8>{
+    match operator{
+        "||"=>Some(2),
+        "&&"=>Some(3),
+        "=="|"!="|"==="|"!=="=>Some(4),
+        ">"|"<"|">="|"<="=>Some(5),
+        "+"|"-"=>Some(6),
+        "%"=>Some(7),
+        "*"|"/"=>Some(8),
+        "^"=>Some(EXPONENT),
+        _=>None
+    }
+}
+/// How tightly a node binds when printed
+fn precedence(node: &ParseNode)->u8{
+    match node{
+        ParseNode::If{..}=>IF,
+        ParseNode::Operator{operator,..}=>binary_precedence(operator).unwrap_or(IF),
+        ParseNode::Roll{..}=>ROLL,
+        ParseNode::Not{..}|ParseNode::UnaryOperator{..}=>UNARY,
+        ParseNode::Call{..}|ParseNode::Index{..}=>POSTFIX,
+        ParseNode::Constant{value,..} if value.as_f64().is_some_and(|n|n<0.0)=>UNARY,
+        _=>ATOM
+    }
+}
+/// Write `node`, wrapping it in parentheses if it binds looser than `min`
+fn write_child(f: &mut fmt::Formatter<'_>, node: &ParseNode, min: u8)->fmt::Result{
+    if precedence(node)<min{
+        write!(f,"({node})")
+    } else {
+        write!(f,"{node}")
+    }
+}
+/// Write a comma seperated list of nodes
+fn write_list(f: &mut fmt::Formatter<'_>, nodes: &[Box<ParseNode>])->fmt::Result{
+    for (i,node) in nodes.iter().enumerate(){
+        if i>0{
+            write!(f,", ")?;
+        }
+        write!(f,"{node}")?;
+    }
+    Ok(())
+}
+/// Whether a printed operand could run into the `d` of a roll and change how it lexes
+fn joins_word(text: &str, at_start: bool)->bool{
+    let c = if at_start {text.chars().next()} else {text.chars().last()};
+    c.is_some_and(|c|c.is_alphanumeric()||c=='_'||c=='.')
+}
+/// Prints the node as dicecloud formula syntax, only adding parentheses where the tree needs them
+///
+/// `Error` nodes don't keep the text that failed to parse, so they print as their message and won't parse back.
+/// [`Calculation::set_parse_node`] keeps the old text rather than printing one
+///
+/// # Examples
+/// ```
+/// use dicecloud_models::{ParseNode, PropVal};
+/// let num = |n|Box::new(ParseNode::Constant{value_type: "number".to_string(), value: PropVal::Number(n)});
+/// let sum = ParseNode::Operator{left: num(1), right: num(2), operator: "+".to_string(), fun: "add".to_string()};
+/// let node = ParseNode::Operator{left: Box::new(sum), right: num(3), operator: "*".to_string(), fun: "multiply".to_string()};
+/// assert_eq!(node.to_string(), "(1 + 2) * 3");
+/// ```
+impl fmt::Display for ParseNode{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Self::Accessor{path,name}=>{
+                write!(f,"{name}")?;
+                path.iter().try_for_each(|p|write!(f,".{p}"))
+            },
+            Self::Array{values}=>{
+                write!(f,"[")?;
+                write_list(f,values)?;
+                write!(f,"]")
+            },
+            Self::Call{function_name,args}=>{
+                write!(f,"{function_name}(")?;
+                write_list(f,args)?;
+                write!(f,")")
+            },
+            Self::Constant{value,..}=>match value{
+                PropVal::Str(s)=>write!(f,"\"{}\"",s.replace('\\',"\\\\").replace('"',"\\\"")),
+                v=>write!(f,"{v}")
+            },
+            Self::Error{error}=>write!(f,"{}",error.message),
+            Self::If{condition,consequent,alternative}=>{
+                write_child(f,condition,IF+1)?;
+                write!(f," ? ")?;
+                write_child(f,consequent,IF+1)?;
+                write!(f," : ")?;
+                write_child(f,alternative,IF)
+            },
+            Self::Index{array,index}=>{
+                write_child(f,array,POSTFIX)?;
+                write!(f,"[{index}]")
+            },
+            Self::Operator{left,right,operator,..}=>{
+                let prec = binary_precedence(operator).unwrap_or(IF);
+                // exponents group to the right (and may have a sign on the right), everything else to the left
+                let (lmin,rmin) = if prec==EXPONENT {(POSTFIX,UNARY)} else {(prec,prec+1)};
+                write_child(f,left,lmin)?;
+                write!(f," {operator} ")?;
+                write_child(f,right,rmin)
+            },
+            Self::Not{right}=>{
+                write!(f,"!")?;
+                write_child(f,right,UNARY)
+            },
+            Self::Parenthesis{content}=>write!(f,"({content})"),
+            Self::Roll{left,right}=>{
+                let lhs = if precedence(left)<ROLL {format!("({left})")} else {left.to_string()};
+                let rhs = if precedence(right)<=ROLL {format!("({right})")} else {right.to_string()};
+                let numeric = |s: &str|s.chars().all(|c|c.is_ascii_digit());
+                let lsep = if joins_word(&lhs,false) && !numeric(&lhs) {" "} else {""};
+                let rsep = if joins_word(&rhs,true) && !numeric(&rhs) {" "} else {""};
+                write!(f,"{lhs}{lsep}d{rsep}{rhs}")
+            },
+            Self::Symbol{name}=>write!(f,"{name}"),
+            Self::UnaryOperator{operator,right}=>{
+                write!(f,"{operator}")?;
+                write_child(f,right,UNARY)
+            }
+        }
+    }
+}
+/// Whether there is an `Error` node anywhere in `node`
+fn has_error(node: &ParseNode)->bool{
+    match node{
+        ParseNode::Error{..}=>true,
+        ParseNode::Array{values: nodes}|ParseNode::Call{args: nodes,..}=>nodes.iter().any(|n|has_error(n)),
+        ParseNode::If{condition,consequent,alternative}=>has_error(condition)||has_error(consequent)||has_error(alternative),
+        ParseNode::Index{array: left,index: right}|ParseNode::Operator{left,right,..}|ParseNode::Roll{left,right}=>has_error(left)||has_error(right),
+        ParseNode::Not{right}|ParseNode::UnaryOperator{right,..}|ParseNode::Parenthesis{content: right}=>has_error(right),
+        ParseNode::Accessor{..}|ParseNode::Constant{..}|ParseNode::Symbol{..}=>false
+    }
+}
+impl Calculation{
+    /// Replace the parse node, regenerating the `calculation` text to match it
+    ///
+    /// If the node has an `Error` in it there is no text to print, so the `calculation` text is left as it was
+    pub fn set_parse_node(&mut self, node: ParseNode){
+        if !has_error(&node){
+            self.calculation = node.to_string();
+        }
+        self.parse_node = node;
+    }
+}
@@ -0,0 +1,78 @@
+//! Working out which properties are live, so removed gear and disabled features can be left out
+//!
+//! A property is active unless it is `inactive`, `removed`, deactivated by a toggle, an ancestor or itself.
+//! When listing active properties, anything under an inactive property is left out too,
+//! even if its own flags haven't caught up yet
+use std::collections::HashSet;
+
+use crate::data_models::flat_model::{FlatCharacter, FlatProp};
+use crate::data_models::generic_model::{PropType, Tracked};
+use crate::data_models::tree_model::{Children, TreeCharacter, TreeProp};
+
+/// The flags shared by flat and tree properties, combined
+const fn flags_active(inactive: bool, removed: bool, by_toggle: Option<bool>, by_ancestor: Option<bool>, prop_type: &PropType)->bool{
+    !inactive && !removed && !matches!(by_toggle,Some(true)) && !matches!(by_ancestor,Some(true)) && !prop_type.is_deactivated_by_self()
+}
+impl FlatProp{
+    /// Whether this property is live on the character
+    ///
+    /// Only looks at this property's own flags, see [`FlatCharacter::active_properties`] to also take its ancestors into account
+    #[must_use]
+    pub const fn is_active(&self)->bool{
+        flags_active(*Tracked::get(&self.inactive),*Tracked::get(&self.removed),*Tracked::get(&self.deactivated_by_toggle),*Tracked::get(&self.deactivated_by_ancestor),&self.prop_type)
+    }
+}
+impl TreeProp{
+    /// Whether this property is live on the character
+    ///
+    /// Only looks at this property's own flags, see [`TreeCharacter::active_properties`] to also take the properties above it into account
+    #[must_use]
+    pub const fn is_active(&self)->bool{
+        flags_active(*Tracked::get(&self.inactive),*Tracked::get(&self.removed),*Tracked::get(&self.deactivated_by_toggle),*Tracked::get(&self.deactivated_by_ancestor),&self.prop_type)
+    }
+}
+impl FlatCharacter{
+    /// The properties that are active, and not under an inactive property, in the order they are in `creature_properties`
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::FlatCharacter;
+    /// let character = FlatCharacter::default();
+    /// assert_eq!(character.active_properties().count(), 0);
+    /// ```
+    pub fn active_properties(&self)->impl Iterator<Item=&FlatProp>{
+        let inactive: HashSet<&str> = self.creature_properties.iter().filter(|p|!p.is_active()).map(|p|p.id.as_str()).collect();
+        self.creature_properties.iter()
+            .filter(move |p|p.is_active() && !p.ancestors.iter().any(|anc|inactive.contains(anc.id.as_str())))
+    }
+}
+/// Walks the active properties of a tree, each before its children, without going under inactive ones
+#[derive(Debug,Clone)]
+pub struct ActiveProps<'a>{
+    stack: Vec<Children<'a>>,
+}
+impl<'a> Iterator for ActiveProps<'a>{
+    type Item = &'a TreeProp;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(level) = self.stack.last_mut(){
+            match level.next(){
+                Some(prop) if prop.is_active()=>{
+                    self.stack.push(prop.children());
+                    return Some(prop);
+                }
+                Some(_)=>{}
+                None=>{
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+impl TreeCharacter{
+    /// The properties that are active, and not under an inactive property, in pre-order
+    #[must_use]
+    pub fn active_properties(&self)->ActiveProps<'_>{
+        ActiveProps{stack: vec![self.roots()]}
+    }
+}
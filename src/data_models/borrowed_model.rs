@@ -0,0 +1,243 @@
+//! A flat model which borrows its strings from the json it was read from
+//!
+//! Reading into these types allocates far less than the owned model, which makes them a better fit for scanning many characters.
+//! Strings are [`Cow`]s, borrowed unless they contain escapes (such as the quotes in most svg icons).
+//! Each property is a [`View`], which keeps the raw json it was read from beside the few fields most code needs:
+//! anything else, such as a calculation, can be read from it when needed, and it converts into the owned model exactly as
+//! [`FlatCharacter::from_json`] would have read it
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::data_models::flat_model::{read_entry, FlatCharacter, FlatProp};
+use crate::data_models::generic_model::{CalculatedText, Calculation, Icon};
+use crate::error::Error;
+
+/// A string borrowed where possible, serde only borrows into a [`Cow`] that isn't inside an `Option` or `Vec` when asked
+#[derive(Deserialize,PartialEq,Eq,Hash)]
+#[serde(transparent)]
+struct Str<'a>(#[serde(borrow)] Cow<'a,str>);
+fn borrow_option<'de: 'a, 'a, D: Deserializer<'de>>(d: D)->Result<Option<Cow<'a,str>>,D::Error>{
+    Ok(Option::<Str<'a>>::deserialize(d)?.map(|s|s.0))
+}
+fn borrow_vec<'de: 'a, 'a, D: Deserializer<'de>>(d: D)->Result<Vec<Cow<'a,str>>,D::Error>{
+    Ok(Vec::<Str<'a>>::deserialize(d)?.into_iter().map(|s|s.0).collect())
+}
+/// The field `key` of the json object `json`, if it is one and has that field
+fn field<'a>(json: &'a str, key: &str)->Option<&'a RawValue>{
+    let fields: HashMap<Str<'a>,&'a RawValue> = serde_json::from_str(json).ok()?;
+    fields.into_iter().find_map(|(k,v)|(k.0==key).then_some(v))
+}
+
+/// Borrowed fields which have a counterpart in the owned model, so a [`View`] of them can be converted into it
+pub trait BorrowedModel{
+    type Owned: DeserializeOwned;
+}
+/// Typed fields borrowed from a json object, along with the raw json itself
+///
+/// Derefs to the fields. The rest of the object can be read with [`get`][Self::get],
+/// and the whole of it converted into the owned model with [`into_owned`][Self::into_owned].
+/// Can only be read straight from json, as serde's `flatten` and `untagged` don't keep raw json
+#[derive(Debug,Clone)]
+pub struct View<'a,T>{
+    fields: T,
+    raw: &'a RawValue
+}
+impl<'a,T> View<'a,T>{
+    /// The json this was read from
+    #[must_use]
+    pub const fn raw(&self)->&'a RawValue{
+        self.raw
+    }
+    /// The field `key` of the json, whether or not it is one of the typed fields
+    #[must_use]
+    pub fn get(&self, key: &str)->Option<&'a RawValue>{
+        field(self.raw.get(),key)
+    }
+}
+impl<T: BorrowedModel> View<'_,T>{
+    /// Convert to the owned model
+    /// # Errors
+    /// If the json doesn't fit the owned model, for example because a property has an unknown type
+    pub fn into_owned(self)->Result<T::Owned,Error>{
+        read_entry(&serde_json::from_str::<Value>(self.raw.get())?,"")
+    }
+}
+impl<T> Deref for View<'_,T>{
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.fields
+    }
+}
+impl<'de: 'a, 'a, T: Deserialize<'a>> Deserialize<'de> for View<'a,T>{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = <&'a RawValue>::deserialize(deserializer)?;
+        let fields = serde_json::from_str(raw.get()).map_err(de::Error::custom)?;
+        Ok(Self{fields, raw})
+    }
+}
+/// Views are equal if they were read from the same json, which also decides their fields
+impl<T> PartialEq for View<'_,T>{
+    fn eq(&self, other: &Self) -> bool {
+        self.raw.get()==other.raw.get()
+    }
+}
+impl<T> Serialize for View<'_,T>{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+/// A borrowed [`Identifier`][crate::data_models::generic_model::Identifier]
+#[derive(Deserialize,PartialEq,Eq,Debug,Clone)]
+pub struct BorrowedIdentifier<'a>{
+    #[serde(borrow)]
+    pub id: Cow<'a,str>,
+    #[serde(borrow)]
+    pub collection: Cow<'a,str>
+}
+/// A borrowed [`Icon`], the svg in `shape` is only borrowed if it has no escaped quotes
+#[derive(Deserialize,PartialEq,Eq,Debug,Clone)]
+pub struct BorrowedIcon<'a>{
+    #[serde(borrow)]
+    pub name: Cow<'a,str>,
+    #[serde(borrow)]
+    pub shape: Cow<'a,str>
+}
+impl BorrowedModel for BorrowedIcon<'_>{
+    type Owned = Icon;
+}
+/// A borrowed [`Calculation`], with just the formula and where it comes from
+#[derive(Deserialize,PartialEq,Eq,Debug,Clone)]
+pub struct BorrowedCalculation<'a>{
+    #[serde(borrow)]
+    pub calculation: Cow<'a,str>,
+    #[serde(rename="_key",borrow)]
+    pub key: Cow<'a,str>,
+    #[serde(rename="type",borrow)]
+    pub typ: Cow<'a,str>
+}
+impl BorrowedModel for BorrowedCalculation<'_>{
+    type Owned = Calculation;
+}
+/// A borrowed [`CalculatedText`], with the text and the value it was last computed to
+#[derive(Deserialize,PartialEq,Eq,Debug,Clone)]
+pub struct BorrowedCalculatedText<'a>{
+    #[serde(borrow)]
+    pub text: Cow<'a,str>,
+    #[serde(borrow)]
+    pub value: Cow<'a,str>
+}
+impl BorrowedModel for BorrowedCalculatedText<'_>{
+    type Owned = CalculatedText;
+}
+/// The fields of a [`FlatProp`] most code needs, borrowed
+///
+/// Everything specific to the property's type is left in the json,
+/// it isn't checked against the type until the [`View`] is converted with [`into_owned`][View::into_owned]
+#[derive(Deserialize,PartialEq,Debug,Clone)]
+#[serde(rename_all="camelCase")]
+pub struct BorrowedFlatProp<'a>{
+    #[serde(rename="_id",borrow)]
+    pub id: Cow<'a,str>,
+    #[serde(rename="type",borrow)]
+    pub typ: Cow<'a,str>,
+    #[serde(default,borrow,deserialize_with="borrow_option")]
+    pub name: Option<Cow<'a,str>>,
+    #[serde(default,borrow,deserialize_with="borrow_option")]
+    pub variable_name: Option<Cow<'a,str>>,
+    #[serde(borrow,deserialize_with="borrow_vec")]
+    pub tags: Vec<Cow<'a,str>>,
+    pub order: usize,
+    #[serde(borrow)]
+    pub parent: BorrowedIdentifier<'a>,
+    #[serde(borrow)]
+    pub ancestors: Vec<BorrowedIdentifier<'a>>,
+    #[serde(default,borrow)]
+    pub icon: Option<View<'a,BorrowedIcon<'a>>>,
+    #[serde(default,borrow,deserialize_with="borrow_vec")]
+    pub library_tags: Vec<Cow<'a,str>>,
+    #[serde(default)]
+    pub inactive: bool,
+    #[serde(default)]
+    pub removed: bool
+}
+impl BorrowedModel for BorrowedFlatProp<'_>{
+    type Owned = FlatProp;
+}
+impl<'a> View<'a,BorrowedFlatProp<'a>>{
+    /// Read the calculation in the field `key`, such as `attackRoll` or `baseValue`
+    #[must_use]
+    pub fn calculation(&self, key: &str)->Option<View<'a,BorrowedCalculation<'a>>>{
+        self.read(key)
+    }
+    #[must_use]
+    pub fn description(&self)->Option<View<'a,BorrowedCalculatedText<'a>>>{
+        self.read("description")
+    }
+    #[must_use]
+    pub fn summary(&self)->Option<View<'a,BorrowedCalculatedText<'a>>>{
+        self.read("summary")
+    }
+    /// Read the field `key` as a `T`, if it is there and fits
+    fn read<T: Deserialize<'a>>(&self, key: &str)->Option<T>{
+        serde_json::from_str(self.get(key)?.get()).ok()
+    }
+}
+/// A borrowed version of [`FlatCharacter`]
+///
+/// Creatures and variables are kept as raw json, other top level fields can be read with [`get`][Self::get]
+///
+/// # Examples
+/// ```
+/// use std::borrow::Cow;
+/// use dicecloud_models::data_models::borrowed_model::BorrowedFlatCharacter;
+/// let json = r#"{"creatures": [], "creatureProperties": [{"_id": "abc", "type": "folder", "name": "Features",
+///     "tags": [], "order": 0, "parent": {"id": "c", "collection": "creatures"}, "ancestors": []}], "creatureVariables": []}"#;
+/// let character = BorrowedFlatCharacter::from_json(json).unwrap();
+/// assert!(matches!(character.creature_properties[0].id, Cow::Borrowed("abc")));
+/// let owned = character.into_owned().unwrap();
+/// assert_eq!(owned.creature_properties[0].id, "abc");
+/// ```
+#[derive(Debug,Clone)]
+pub struct BorrowedFlatCharacter<'a>{
+    pub creatures: Vec<&'a RawValue>,
+    pub creature_properties: Vec<View<'a,BorrowedFlatProp<'a>>>,
+    pub creature_variables: Vec<&'a RawValue>,
+    json: &'a str
+}
+#[derive(Deserialize)]
+#[serde(rename_all="camelCase")]
+struct CharacterParts<'a>{
+    #[serde(borrow)]
+    creatures: Vec<&'a RawValue>,
+    #[serde(borrow)]
+    creature_properties: Vec<View<'a,BorrowedFlatProp<'a>>>,
+    #[serde(borrow)]
+    creature_variables: Vec<&'a RawValue>
+}
+impl<'a> BorrowedFlatCharacter<'a>{
+    /// Read a character from json, borrowing strings from it where possible
+    /// # Errors
+    /// If the json is not valid or doesn't fit the expected format
+    pub fn from_json(json: &'a str)->Result<Self,Error>{
+        let CharacterParts{creatures, creature_properties, creature_variables} = serde_json::from_str(json)?;
+        Ok(Self{creatures, creature_properties, creature_variables, json})
+    }
+    /// The top level field `key` of the character's json, such as `exportedAt`
+    #[must_use]
+    pub fn get(&self, key: &str)->Option<&'a RawValue>{
+        field(self.json,key)
+    }
+    /// Convert to the owned model
+    /// # Errors
+    /// If any part of the character doesn't fit a [`FlatCharacter`], with the error located as in [`FlatCharacter::from_json`]
+    pub fn into_owned(self)->Result<FlatCharacter,Error>{
+        FlatCharacter::from_json(self.json)
+    }
+}
@@ -0,0 +1,45 @@
+//! Working out how much damage a creature takes, from its damage multipliers
+use crate::data_models::flat_model::FlatCharacter;
+use crate::data_models::generic_model::{DamageType, PropType};
+
+impl FlatCharacter{
+    /// The multiplier the creature applies to damage of `damage_type` carrying `tags`
+    ///
+    /// Only active damage multipliers count, and only those whose `include_tags` are all on the damage and whose `exclude_tags` aren't.
+    /// Like dicecloud, any immunity gives 0, otherwise resistance halves and vulnerability doubles, each applying at most once
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::{DamageType, FlatCharacter};
+    /// let character = FlatCharacter::default();
+    /// assert_eq!(character.damage_multiplier(&DamageType::Fire, &["magical"]), 1.0);
+    /// ```
+    #[must_use]
+    pub fn damage_multiplier(&self, damage_type: &DamageType, tags: &[&str])->f64{
+        let has = |tag: &String|tags.contains(&tag.as_str());
+        let mut immune = false;
+        let mut resistant = false;
+        let mut vulnerable = false;
+        for prop in self.active_properties(){
+            let PropType::DamageMultiplier{damage_types,value,include_tags,exclude_tags,..} = &prop.prop_type else {
+                continue;
+            };
+            if !damage_types.contains(damage_type) || !include_tags.iter().all(has) || exclude_tags.iter().any(has){
+                continue;
+            }
+            if *value==0.0{
+                immune = true;
+            } else if *value<1.0{
+                resistant = true;
+            } else if *value>1.0{
+                vulnerable = true;
+            }
+        }
+        match (immune,resistant,vulnerable){
+            (true,..)=>0.0,
+            (false,true,false)=>0.5,
+            (false,false,true)=>2.0,
+            _=>1.0
+        }
+    }
+}
@@ -1,45 +1,56 @@
 use std::collections::HashMap;
 
-use crate::data_models::generic_model::{CharacterVar, CreatureInfo, Icon, Identifier, PropType};
+use crate::data_models::generic_model::{serialize_prop_type, CharacterVar, CreatureInfo, Extra, Icon, Identifier, PropType, Tracked};
+#[cfg(feature="serde_json")]
+use crate::data_models::generic_model::PropKind;
 use crate::data_models::tree_model::TreeCharacter;
+#[cfg(feature="serde_json")]
+use crate::error::Error;
 use serde::{Serialize,Deserialize};
+#[cfg(feature="serde_json")]
+use serde::de::DeserializeOwned;
+#[cfg(feature="serde_json")]
+use serde_json::{Map,Value};
 
 /// Represents all the details of a property as represented by dicecloud
+/// 
+/// Any fields that aren't modelled are kept in the `extra` of the property type
 #[derive(Serialize, Deserialize,PartialEq,Debug,Default,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct FlatProp{
     #[serde(rename="_id")]
     pub id: String,
     #[serde(flatten)]
+    #[serde(serialize_with="serialize_prop_type")]
     pub prop_type: PropType,
     pub tags: Vec<String>,
     pub order: usize,
     pub parent: Identifier,
     pub ancestors: Vec<Identifier>,
-    #[serde(default)]
-    pub library_node_id: Option<String>,
-    #[serde(default)]
-    pub color: Option<String>,
-    #[serde(default)]
-    pub icon: Option<Icon>,
-    #[serde(default)]
-    pub library_tags: Vec<String>,
-    #[serde(default)]
-    pub deactivated_by_toggle: Option<bool>,
-    #[serde(default)]
-    pub deactivated_by_ancestor: Option<bool>,
-    #[serde(default)]
-    pub inactive: bool,
-    #[serde(default)]
-    pub removed: bool,
-    #[serde(default)]
-    pub removed_at: Option<String>,
-    #[serde(default)]
-    pub removed_with: Option<String>,
-    #[serde(default)]
-    pub dirty: Option<bool>,
-    #[serde(default,rename="_migrationError")]
-    pub migration_error: Vec<String>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub library_node_id: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub color: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub icon: Tracked<Option<Icon>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub library_tags: Tracked<Vec<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub deactivated_by_toggle: Tracked<Option<bool>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub deactivated_by_ancestor: Tracked<Option<bool>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub inactive: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed_at: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed_with: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub dirty: Tracked<Option<bool>>,
+    #[serde(default,rename="_migrationError",skip_serializing_if="Tracked::is_absent")]
+    pub migration_error: Tracked<Vec<String>>
 }
 impl PartialOrd for FlatProp{
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
@@ -52,32 +63,38 @@ impl PartialOrd for FlatProp{
 /// Use `creature_variables` if you just want to process the variables for a creature
 /// 
 /// Or `creature_properties` if you need the properties
-#[derive(Serialize, Deserialize,PartialEq,Default,Clone)]
+#[derive(Serialize, Deserialize,PartialEq,Debug,Default,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct FlatCharacter{
     pub creatures: Vec<CreatureInfo>,
     pub creature_properties: Vec<FlatProp>,
-    pub creature_variables: Vec<HashMap<String, CharacterVar>>
+    pub creature_variables: Vec<HashMap<String, CharacterVar>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 impl FlatCharacter{
     /// Convert a character in tree form back to its standard flat form.
     /// 
     /// Usefull if you want to make changes in tree form and then re-export
+    /// 
+    /// Properties come out in pre-order, each parent followed by its children, all in `order`
     #[allow(clippy::use_self)]
     #[must_use]
     pub fn from_tree_char(tree_char: TreeCharacter)->FlatCharacter{
         let creatures= tree_char.creatures;
         let creature_variables=tree_char.creature_variables;
+        let extra=tree_char.extra;
         let mut creature_properties: Vec<FlatProp> = Vec::new();
-        for prop in tree_char.creature_properties_tmap.into_values(){
+        for prop in tree_char.creature_properties_tree{
             prop.flatten(&mut creature_properties);
         }
-        Self { creatures, creature_properties, creature_variables}
+        Self { creatures, creature_properties, creature_variables, extra}
     }
     /// Convert json return from dcv2 into a flat character
     /// 
     /// # Errors
-    /// If the json is not valid or doesn't fit the expected format
+    /// If the json is not valid or doesn't fit the expected format.
+    /// When a single creature, property or variable map is at fault the error says which one
     /// # Examples
     #[cfg_attr(doctest, doc = " ````no_test")]
     /// ```
@@ -87,10 +104,143 @@ impl FlatCharacter{
     /// let character = FlatCharacter::from_json(&character_string);
     /// // process character
     /// ````
-
     #[cfg(feature="serde_json")]
     #[allow(clippy::use_self)]
-    pub fn from_json(json: &str)->serde_json::Result<FlatCharacter>{
-        serde_json::from_str(json)
+    pub fn from_json(json: &str)->Result<FlatCharacter,Error>{
+        match serde_json::from_str(json){
+            Ok(ch)=>Ok(ch),
+            Err(e) if e.is_data()=>Err(locate_error(json,e)),
+            Err(e)=>Err(Error::Json(e))
+        }
+    }
+    /// Convert json from dcv2 into a flat character, keeping properties that can't be read
+    /// 
+    /// Each property is read on its own. Any that fail (for example because dicecloud added a new property type)
+    /// are kept as [`PropType::Unknown`] and reported in the returned list of warnings, rather than failing the whole import.
+    /// Unknown properties are written back out exactly as they were read
+    /// # Errors
+    /// If the json is not valid, or the creatures or variables don't fit the expected format
+    #[cfg(feature="serde_json")]
+    #[allow(clippy::use_self)]
+    pub fn from_json_lenient(json: &str)->Result<(FlatCharacter,Vec<Error>),Error>{
+        let Value::Object(mut root) = serde_json::from_str(json)? else {
+            return Err(Error::InvalidField{id: None, path: String::new(), message: "expected an object".to_string()});
+        };
+        let creatures = read_part(&root,"creatures")?;
+        let creature_variables = read_part(&root,"creatureVariables")?;
+        let raw_props = match root.remove("creatureProperties"){
+            Some(Value::Array(props))=>props,
+            Some(_)=>return Err(Error::InvalidField{id: None, path: "creatureProperties".to_string(), message: "expected an array".to_string()}),
+            None=>return Err(Error::MissingField{id: None, path: String::new(), field: "creatureProperties".to_string()})
+        };
+        let mut warnings = Vec::new();
+        let mut creature_properties = Vec::with_capacity(raw_props.len());
+        for (i,raw) in raw_props.into_iter().enumerate(){
+            match read_entry(&raw,&format!("creatureProperties[{i}]")){
+                Ok(prop)=>creature_properties.push(prop),
+                Err(e)=>{
+                    creature_properties.push(FlatProp::unknown(&raw,e.to_string()));
+                    warnings.push(e);
+                }
+            }
+        }
+        root.remove("creatures");
+        root.remove("creatureVariables");
+        Ok((Self{creatures, creature_properties, creature_variables, extra: root.into()},warnings))
     }
-}
\ No newline at end of file
+}
+/// Work out which part of a character caused a data error, by deserializing each part on its own
+#[cfg(feature="serde_json")]
+fn locate_error(json: &str, err: serde_json::Error)->Error{
+    let Ok(Value::Object(root)) = serde_json::from_str::<Value>(json) else {
+        return Error::Json(err);
+    };
+    read_part::<CreatureInfo>(&root,"creatures")
+        .and_then(|_|read_part::<FlatProp>(&root,"creatureProperties"))
+        .and_then(|_|read_part::<HashMap<String,CharacterVar>>(&root,"creatureVariables"))
+        .err().unwrap_or(Error::Json(err))
+}
+/// Deserialize each entry of the array `key` seperately, so any error can say which entry failed
+#[cfg(feature="serde_json")]
+fn read_part<T: DeserializeOwned>(root: &Map<String,Value>, key: &str)->Result<Vec<T>,Error>{
+    let Some(part) = root.get(key) else {
+        return Err(Error::MissingField{id: None, path: String::new(), field: key.to_string()});
+    };
+    let Value::Array(items) = part else {
+        return Err(Error::InvalidField{id: None, path: key.to_string(), message: "expected an array".to_string()});
+    };
+    items.iter().enumerate()
+        .map(|(i,item)|read_entry(item,&format!("{key}[{i}]")))
+        .collect()
+}
+/// Deserialize one entry of a character, with any error pointing at the field that caused it
+///
+/// serde can't follow the path into the flattened property type, so errors in its fields point at the property itself.
+/// A `type` that isn't one we know is checked for first, so it is reported as an unknown property type
+#[cfg(feature="serde_json")]
+pub(crate) fn read_entry<T: DeserializeOwned>(item: &Value, path: &str)->Result<T,Error>{
+    serde_path_to_error::deserialize(item).map_err(|err|{
+        let field = err.path().to_string();
+        let err = err.into_inner();
+        if field=="."{
+            if let Some(typ) = item.get("type").and_then(Value::as_str).filter(|t|PropKind::from(*t).known_str().is_none()){
+                return Error::UnknownPropType{id: id_of(item), path: format!("{path}.type"), typ: typ.to_string()};
+            }
+        }
+        Error::at(&err,field_path(path,&field),id_of(item))
+    })
+}
+/// The path to `field` inside the object at `path`
+#[cfg(feature="serde_json")]
+pub(crate) fn field_path(path: &str, field: &str)->String{
+    match field{
+        ""|"."=>path.to_string(),
+        field if field.starts_with('[') || path.is_empty()=>format!("{path}{field}"),
+        field=>format!("{path}.{field}")
+    }
+}
+/// The `_id` of a raw json object, if it has one
+#[cfg(feature="serde_json")]
+pub(crate) fn id_of(item: &Value)->Option<String>{
+    item.get("_id").and_then(Value::as_str).map(str::to_string)
+}
+/// The fields that belong to the property itself rather than its type
+#[cfg(feature="serde_json")]
+const PROP_KEYS: [&str; 18] = ["_id","tags","order","parent","ancestors","libraryNodeId","color","icon","libraryTags",
+    "deactivatedByToggle","deactivatedByAncestor","inactive","removed","removedAt","removedWith","dirty","_migrationError","children"];
+/// Read a single field out of raw json, falling back to the default if it is missing or malformed
+#[cfg(feature="serde_json")]
+fn salvage<T: DeserializeOwned+Default>(raw: &Value, key: &str)->T{
+    raw.get(key).and_then(|v|T::deserialize(v).ok()).unwrap_or_default()
+}
+#[cfg(feature="serde_json")]
+impl FlatProp{
+    /// Wrap a property that failed to deserialize, keeping whatever common fields can still be read
+    fn unknown(raw: &Value, error: String)->Self{
+        let Some(mut fields) = raw.as_object().cloned() else {
+            return Self{prop_type: PropType::Unknown{typ: None, error, extra: Extra::default()}, ..Self::default()};
+        };
+        let typ = fields.get("type").and_then(Value::as_str).map(str::to_string);
+        fields.retain(|k,_|!(PROP_KEYS.contains(&k.as_str()) || k=="type" && typ.is_some()));
+        Self{
+            id: salvage(raw,"_id"),
+            tags: salvage(raw,"tags"),
+            order: salvage(raw,"order"),
+            parent: salvage(raw,"parent"),
+            ancestors: salvage(raw,"ancestors"),
+            library_node_id: salvage(raw,"libraryNodeId"),
+            color: salvage(raw,"color"),
+            icon: salvage(raw,"icon"),
+            library_tags: salvage(raw,"libraryTags"),
+            deactivated_by_toggle: salvage(raw,"deactivatedByToggle"),
+            deactivated_by_ancestor: salvage(raw,"deactivatedByAncestor"),
+            inactive: salvage(raw,"inactive"),
+            removed: salvage(raw,"removed"),
+            removed_at: salvage(raw,"removedAt"),
+            removed_with: salvage(raw,"removedWith"),
+            dirty: salvage(raw,"dirty"),
+            migration_error: salvage(raw,"_migrationError"),
+            prop_type: PropType::Unknown{typ, error, extra: fields.into()}
+        }
+    }
+}
@@ -1,42 +1,262 @@
+// serde's generated code for flattened enums trips this lint
+#![allow(clippy::collection_is_never_read)]
 use core::fmt;
 use std::default::{Default};
-use serde::{Serialize,Deserialize};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use serde::{Serialize,Deserialize,Serializer,Deserializer};
+#[cfg(not(feature="serde_json"))]
+use serde::ser::SerializeMap;
+#[cfg(feature="serde_json")]
+use serde_json::{Map,Value};
 
-
-
-#[derive(Serialize, Deserialize,PartialEq,Eq,Debug,Default,Hash,Clone,Copy)]
+/// A field dicecloud doesn't always write, which remembers whether it was there
+/// 
+/// Derefs to its value, so it reads like the value itself.
+/// A field that wasn't in the json is left out again when writing, while one that was is written back even if it is `false`, `[]` or `null`.
+/// Changing the value through a mutable reference, or making one with `From`, marks it as present
+#[derive(Clone,Copy,Default)]
+pub struct Tracked<T>{
+    value: T,
+    present: bool
+}
+// these are associated functions rather than methods, like `Rc`'s, so they don't hide methods of the value
+impl<T> Tracked<T>{
+    /// A value which is written out
+    pub const fn new(value: T)->Self{
+        Self{value, present: true}
+    }
+    /// Whether the field was in the json, or has been set since
+    #[must_use]
+    pub const fn is_present(this: &Self)->bool{
+        this.present
+    }
+    pub(crate) const fn is_absent(this: &Self)->bool{
+        !this.present
+    }
+    /// The value, like deref but usable in const fns
+    #[must_use]
+    pub const fn get(this: &Self)->&T{
+        &this.value
+    }
+    /// Take the value out, whether or not it was present
+    pub fn into_inner(this: Self)->T{
+        this.value
+    }
+    /// Convert the value, keeping whether it was present
+    pub fn map<U>(this: Self, f: impl FnOnce(T)->U)->Tracked<U>{
+        Tracked{value: f(this.value), present: this.present}
+    }
+}
+impl<T> std::ops::Deref for Tracked<T>{
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T> std::ops::DerefMut for Tracked<T>{
+    fn deref_mut(&mut self) -> &mut T {
+        self.present = true;
+        &mut self.value
+    }
+}
+impl<T> From<T> for Tracked<T>{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+// the rest only look at the value, two fields holding the same thing are equal whether or not they were written
+impl<T: fmt::Debug> fmt::Debug for Tracked<T>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+impl<T: PartialEq> PartialEq for Tracked<T>{
+    fn eq(&self, other: &Self) -> bool {
+        self.value==other.value
+    }
+}
+impl<T: Eq> Eq for Tracked<T>{}
+impl<T: PartialOrd> PartialOrd for Tracked<T>{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+impl<T: Ord> Ord for Tracked<T>{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+impl<T: Hash> Hash for Tracked<T>{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+impl<T: Serialize> Serialize for Tracked<T>{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tracked<T>{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+/// An `f64` written the way javascript writes numbers, so a whole number read as `1` isn't written back as `1.0`
+struct JsNumber(f64);
+impl Serialize for JsNumber{
+    #[allow(clippy::float_cmp,clippy::cast_possible_truncation)]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // past 2^53 an f64 can't hold every whole number anyway
+        if self.0.fract()==0.0 && self.0.abs()<9_007_199_254_740_992.0{
+            serializer.serialize_i64(self.0 as i64)
+        } else {
+            serializer.serialize_f64(self.0)
+        }
+    }
+}
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_number<S: Serializer>(value: &f64, serializer: S)->Result<S::Ok,S::Error>{
+    JsNumber(*value).serialize(serializer)
+}
+#[allow(clippy::ref_option,clippy::trivially_copy_pass_by_ref)]
+fn serialize_optional_number<S: Serializer>(value: &Option<f64>, serializer: S)->Result<S::Ok,S::Error>{
+    value.map(JsNumber).serialize(serializer)
+}
+/// Holds any fields of an object which aren't modelled, so they can be written back out unchanged
+/// 
+/// Every struct (and property type) has one of these flattened into it.
+/// Without the `serde_json` feature there is nowhere to keep the values, so unknown fields are dropped
+#[derive(PartialEq,Eq,Debug,Default,Clone)]
+pub struct Extra{
+    #[cfg(feature="serde_json")]
+    map: Map<String,Value>
+}
+impl Extra{
+    /// The number of unknown fields
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn len(&self)->usize{
+        #[cfg(feature="serde_json")]
+        return self.map.len();
+        #[cfg(not(feature="serde_json"))]
+        0
+    }
+    /// Whether there are no unknown fields
+    #[must_use]
+    pub fn is_empty(&self)->bool{
+        self.len()==0
+    }
+    /// The unknown fields, by name
+    #[cfg(feature="serde_json")]
+    #[must_use]
+    pub const fn as_map(&self)->&Map<String,Value>{
+        &self.map
+    }
+    /// Mutable access to the unknown fields, by name
+    #[cfg(feature="serde_json")]
+    pub const fn as_map_mut(&mut self)->&mut Map<String,Value>{
+        &mut self.map
+    }
+    /// Look up an unknown field
+    #[cfg(feature="serde_json")]
+    #[must_use]
+    pub fn get(&self, key: &str)->Option<&Value>{
+        self.map.get(key)
+    }
+}
+#[cfg(feature="serde_json")]
+impl From<Map<String,Value>> for Extra{
+    fn from(map: Map<String,Value>) -> Self {
+        Self{map}
+    }
+}
+impl Serialize for Extra{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature="serde_json")]
+        return self.map.serialize(serializer);
+        #[cfg(not(feature="serde_json"))]
+        serializer.serialize_map(Some(0))?.end()
+    }
+}
+impl<'de> Deserialize<'de> for Extra{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[cfg(feature="serde_json")]
+        return Map::deserialize(deserializer).map(|map|Self{map});
+        #[cfg(not(feature="serde_json"))]
+        deserializer.deserialize_map(serde::de::IgnoredAny).map(|_|Self{})
+    }
+}
+impl Extra{
+    /// The fields in a form that can be hashed and ordered, since json values can't be
+    #[cfg(feature="serde_json")]
+    fn keyed(&self)->impl Iterator<Item=(&String,String)>{
+        self.map.iter().map(|(k,v)|(k,v.to_string()))
+    }
+}
+impl Hash for Extra{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        #[cfg(feature="serde_json")]
+        self.keyed().for_each(|entry|entry.hash(state));
+        #[cfg(not(feature="serde_json"))]
+        let _ = state;
+    }
+}
+impl PartialOrd for Extra{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Extra{
+    fn cmp(&self, other: &Self) -> Ordering {
+        #[cfg(feature="serde_json")]
+        return self.keyed().cmp(other.keyed());
+        #[cfg(not(feature="serde_json"))]
+        {
+            let _ = other;
+            Ordering::Equal
+        }
+    }
+}
+#[derive(Serialize, Deserialize,PartialEq,Eq,Debug,Default,Hash,Clone)]
 /// A structure to store the death save info as in the creature settings
 pub struct DeathSaveInfo{
     pub pass: usize,
     pub fail: usize,
     #[serde(rename = "canDeathSave")]
     pub can_death_save: bool,
-    pub stable: bool
+    pub stable: bool,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// A structure to store the denormalized stats(such as xp and milestone levels) as in the creature settings
-#[derive(Serialize,Deserialize, PartialEq,Eq,Debug,Default,Hash,Clone,Copy,PartialOrd, Ord)]
+#[derive(Serialize,Deserialize, PartialEq,Eq,Debug,Default,Hash,Clone,PartialOrd, Ord)]
 pub struct DenormalizedStats{
     #[serde(rename = "milestoneLevels")]
     pub milestone_levels: usize,
     pub xp: usize,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// A structure to deal with a character's advanced settings, such as wether to show the tree tab
 #[derive(Serialize,Deserialize,Default, PartialEq,Debug,Clone)]
 #[serde(rename_all="camelCase")]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Settings{
-    #[serde(default)]
-    pub show_tree_tab: bool,
-    #[serde(default)]
-    pub hide_rest_buttons: bool,
-    #[serde(default)]
-    pub hide_unused_stats: bool,
-    #[serde(default)]
-    pub hide_spells_tab: bool,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub hit_dice_reset_multiplier: Option<f64>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub discord_webhook: Option<String>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub show_tree_tab: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub hide_rest_buttons: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub hide_unused_stats: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub hide_spells_tab: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_optional_number")]
+    pub hit_dice_reset_multiplier: Tracked<Option<f64>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub discord_webhook: Tracked<Option<String>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// A structure to deal with a character's overall settings
 #[derive(Serialize, Deserialize,PartialEq,Debug,Default,Clone)]
@@ -54,16 +274,18 @@ pub struct CreatureInfo{
     pub denormalized_stats: DenormalizedStats,
     #[serde(rename = "type")]
     pub typ: String,
-    //damageMultipliers and variables properties omitted
-    #[serde(default)]
-    pub settings: Settings,
+    //damageMultipliers and variables properties aren't modelled, they end up in extra
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub settings: Tracked<Settings>,
     pub readers: Vec<String>,
     pub writers: Vec<String>,
     pub public: bool,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub picture: Option<String>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub avatar_picture: Option<String>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub picture: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub avatar_picture: Tracked<Option<String>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 #[derive(Serialize,Deserialize,Debug,Clone)]
 #[serde(untagged,rename_all="camelCase")]
@@ -96,11 +318,11 @@ pub enum PropVal{
 impl PartialEq for PropVal{
     fn eq(&self, other: &Self) -> bool {
         match  (&self,other) {
-            (PropVal::Boolean(b1),PropVal::Boolean(b2))=>b1.eq(b2),
-            (PropVal::None(_),PropVal::None(_))=>true,
-            (PropVal::Number(n1),PropVal::Number(n2))=>n1.eq(n2),
-            (PropVal::Fraction(f1),PropVal::Fraction(f2))=>f1.eq(f2),
-            (PropVal::Str(s1),PropVal::Str(s2))=>s1.eq(s2),
+            (Self::Boolean(b1),Self::Boolean(b2))=>b1.eq(b2),
+            (Self::None(_),Self::None(_))=>true,
+            (Self::Number(n1),Self::Number(n2))=>n1.eq(n2),
+            (Self::Fraction(f1),Self::Fraction(f2))=>f1.eq(f2),
+            (Self::Str(s1),Self::Str(s2))=>s1.eq(s2),
             (_,_)=>false
         }
     }
@@ -181,7 +403,9 @@ impl fmt::Display for PropVal{
 /// Wraps a value in a structure (relevant for effects)
 #[derive(Serialize,Deserialize,PartialEq,Debug,Default,Clone)]
 pub struct ValWrap{
-    pub value: PropVal
+    pub value: PropVal,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Encapsulates a `ParseNode` for calculations.
 /// 
@@ -190,18 +414,18 @@ pub struct ValWrap{
 #[serde(rename_all="camelCase",tag="parseType")]
 pub enum ParseNode{
     Accessor{path: Vec<String>,name: String},
-    Array{values: Vec<Box<ParseNode>>},
-    Call{#[serde(rename="functionName")]function_name: String,args: Vec<Box<ParseNode>>},
+    Array{values: Vec<Box<Self>>},
+    Call{#[serde(rename="functionName")]function_name: String,args: Vec<Box<Self>>},
     Error{error: ParseError},
     Constant{#[serde(rename="valueType")]value_type: String, value: PropVal},
-    If{condition: Box<ParseNode>,consequent: Box<ParseNode>,alternative: Box<ParseNode>},
-    Index{array: Box<ParseNode>,index:Box<ParseNode>},
-    Operator{left: Box<ParseNode>,right: Box<ParseNode>,operator: String,#[serde(rename="fn")] fun: String},
-    Not{right: Box<ParseNode>},
-    Parenthesis{content: Box<ParseNode>},
-    Roll{left: Box<ParseNode>, right: Box<ParseNode>},
+    If{condition: Box<Self>,consequent: Box<Self>,alternative: Box<Self>},
+    Index{array: Box<Self>,index:Box<Self>},
+    Operator{left: Box<Self>,right: Box<Self>,operator: String,#[serde(rename="fn")] fun: String},
+    Not{right: Box<Self>},
+    Parenthesis{content: Box<Self>},
+    Roll{left: Box<Self>, right: Box<Self>},
     Symbol{name: String},
-    UnaryOperator{operator: String, right: Box<ParseNode>},
+    UnaryOperator{operator: String, right: Box<Self>},
 }
 impl Default for ParseNode{
     fn default()->Self{
@@ -216,6 +440,15 @@ pub struct ParseError{
     #[serde(rename="type")]
     pub typ: String,
     pub message: String,
+    #[serde(flatten)]
+    pub extra: Extra
+}
+impl ParseError{
+    /// Create an error of the given type (such as `info` or `error`) with a message
+    #[must_use]
+    pub fn new(typ: &str, message: &str)->Self{
+        Self{typ: typ.to_string(), message: message.to_string(), extra: Extra::default()}
+    }
 }
 /// Represents a Calculation as in a calculated field
 /// 
@@ -233,10 +466,12 @@ pub struct Calculation{
     pub parse_node: ParseNode,
     pub errors: Vec<ParseError>,
     pub value: PropVal,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub base_value: Option<PropVal>,
-    #[serde(default)]
-    pub effects: Vec<Effect>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub base_value: Tracked<Option<PropVal>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub effects: Tracked<Vec<Effect>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// A simpler version of the calculation type for calculated text fields.
 /// 
@@ -253,13 +488,293 @@ pub struct SimpleCalc{
     pub parse_node: ParseNode,
     pub errors: Vec<ParseError>,
     pub value: PropVal,
+    #[serde(flatten)]
+    pub extra: Extra
+}
+/// Declares an enum of the strings dicecloud uses for a field, with a fallback variant for any others
+/// 
+/// Each variant (de)serializes as exactly its string, and the fallback keeps whatever else was read so it is written back out unchanged
+macro_rules! string_enum{
+    ($(#[$meta:meta])* $name:ident, $fallback:ident{$($(#[$vmeta:meta])* $variant:ident=>$s:literal),* $(,)?})=>{
+        $(#[$meta])*
+        #[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Hash,Clone)]
+        #[serde(from="String",into="String")]
+        pub enum $name{
+            $($(#[$vmeta])* $variant,)*
+            /// Any other string, kept as it was read
+            $fallback(String)
+        }
+        impl $name{
+            /// The string dicecloud uses for this
+            #[must_use]
+            pub fn as_str(&self)->&str{
+                match self{
+                    $(Self::$variant=>$s,)*
+                    Self::$fallback(s)=>s
+                }
+            }
+            /// The string for a known variant, not borrowed from `self` like [`Self::as_str`]
+            #[allow(dead_code)]
+            pub(crate) const fn known_str(&self)->Option<&'static str>{
+                match self{
+                    $(Self::$variant=>Some($s),)*
+                    Self::$fallback(_)=>None
+                }
+            }
+        }
+        impl From<String> for $name{
+            fn from(s: String) -> Self {
+                match s.as_str(){
+                    $($s=>Self::$variant,)*
+                    _=>Self::$fallback(s)
+                }
+            }
+        }
+        impl From<&str> for $name{
+            fn from(s: &str) -> Self {
+                s.to_string().into()
+            }
+        }
+        impl From<$name> for String{
+            fn from(value: $name) -> Self {
+                match value{
+                    $name::$fallback(s)=>s,
+                    value=>value.as_str().to_string()
+                }
+            }
+        }
+        impl fmt::Display for $name{
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+string_enum!{
+    /// The collection an [`Identifier`] points into
+    ///
+    /// Collections we don't know about are kept as `Unknown`, so they are written back out unchanged
+    Collection, Unknown{
+        Creatures=>"creatures",
+        CreatureProperties=>"creatureProperties",
+        LibraryNodes=>"libraryNodes",
+    }
+}
+impl Default for Collection{
+    fn default() -> Self {
+        Self::Unknown(String::new())
+    }
+}
+string_enum!{
+    /// How long an action or spell takes to use
+    ActionType, Other{
+        Action=>"action",
+        Bonus=>"bonus",
+        Attack=>"attack",
+        Reaction=>"reaction",
+        Free=>"free",
+        /// Takes longer than a turn, like a ritual
+        Long=>"long",
+        /// Happens in response to something, rather than being taken
+        Event=>"event",
+    }
+}
+string_enum!{
+    /// Who a property applies to
+    Target, Other{
+        Self_=>"self",
+        /// The target of the action or spell this is part of
+        Target=>"target",
+        SingleTarget=>"singleTarget",
+        MultipleTargets=>"multipleTargets",
+    }
+}
+string_enum!{
+    /// When uses of an action, spell or attribute come back
+    Reset, Other{
+        ShortRest=>"shortRest",
+        LongRest=>"longRest",
+    }
+}
+string_enum!{
+    /// What an effect does to the stats it targets
+    EffectOperation, Other{
+        /// Sets the base value, the highest base is used
+        Base=>"base",
+        Add=>"add",
+        Mul=>"mul",
+        /// The value can't go below this
+        Min=>"min",
+        /// The value can't go above this
+        Max=>"max",
+        /// Overrides the value entirely
+        Set=>"set",
+        Advantage=>"advantage",
+        Disadvantage=>"disadvantage",
+        /// Adds to the passive score of a skill
+        PassiveAdd=>"passiveAdd",
+        /// Checks automatically fail
+        Fail=>"fail",
+        /// Adds a note of a conditional benefit
+        Conditional=>"conditional",
+    }
+}
+impl EffectOperation{
+    /// Whether the operation changes the value of the stat using its amount
+    #[must_use]
+    pub const fn is_numeric(&self)->bool{
+        matches!(self,Self::Base|Self::Add|Self::Mul|Self::Min|Self::Max|Self::Set|Self::PassiveAdd)
+    }
+    /// Whether the operation changes how rolls against the stat are made, rather than the value
+    #[must_use]
+    pub const fn is_roll(&self)->bool{
+        matches!(self,Self::Advantage|Self::Disadvantage|Self::Fail)
+    }
+    /// Whether the operation only adds text to the stat
+    #[must_use]
+    pub const fn is_textual(&self)->bool{
+        matches!(self,Self::Conditional)
+    }
+}
+impl Default for EffectOperation{
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+string_enum!{
+    /// How an [`ExtraTag`] combines its tags with the rest
+    ExtraTagOperation, Other{
+        /// Properties with these tags also match
+        Or=>"OR",
+        /// Properties with these tags don't match
+        Not=>"NOT",
+    }
+}
+impl Default for ExtraTagOperation{
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+string_enum!{
+    /// The type of damage dealt, anything dicecloud doesn't know of is kept as `Custom`
+    DamageType, Custom{
+        Acid=>"acid",
+        Bludgeoning=>"bludgeoning",
+        Cold=>"cold",
+        Fire=>"fire",
+        Force=>"force",
+        Lightning=>"lightning",
+        Necrotic=>"necrotic",
+        Piercing=>"piercing",
+        Poison=>"poison",
+        Psychic=>"psychic",
+        Radiant=>"radiant",
+        Slashing=>"slashing",
+        Thunder=>"thunder",
+        /// Restores hit points instead of removing them
+        Healing=>"healing",
+        /// Grants temporary hit points
+        TempHp=>"tempHP",
+    }
+}
+impl DamageType{
+    /// Whether this adds hit points rather than taking them away
+    #[must_use]
+    pub const fn is_healing(&self)->bool{
+        matches!(self,Self::Healing|Self::TempHp)
+    }
+}
+string_enum!{
+    /// The school of magic a spell belongs to
+    SpellSchool, Other{
+        Abjuration=>"abjuration",
+        Conjuration=>"conjuration",
+        Divination=>"divination",
+        Enchantment=>"enchantment",
+        Evocation=>"evocation",
+        Illusion=>"illusion",
+        Necromancy=>"necromancy",
+        Transmutation=>"transmutation",
+    }
+}
+impl Default for SpellSchool{
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+string_enum!{
+    /// What a trigger reacts to
+    TriggerEvent, Other{
+        /// A property of an action or spell being applied, eg its damage
+        DoActionProperty=>"doActionProperty",
+        /// An attribute being damaged or healed
+        DamageProperty=>"damageProperty",
+        /// A check being rolled
+        Check=>"check",
+        /// A toggle being switched
+        FlipToggle=>"flipToggle",
+        ShortRest=>"shortRest",
+        LongRest=>"longRest",
+        /// Either kind of rest
+        AnyRest=>"anyRest",
+    }
+}
+impl TriggerEvent{
+    /// Whether a trigger listening for this fires when `event` happens, ie they're the same or this is `AnyRest` and `event` is a rest
+    #[must_use]
+    pub fn fires_on(&self, event: &Self)->bool{
+        self==event || (*self==Self::AnyRest && matches!(event,Self::ShortRest|Self::LongRest))
+    }
+    /// Whether the event happens to a particular property, rather than the whole creature
+    #[must_use]
+    pub const fn has_property(&self)->bool{
+        matches!(self,Self::DoActionProperty|Self::DamageProperty|Self::Check|Self::FlipToggle)
+    }
+}
+string_enum!{
+    /// Whether a trigger fires before or after its event
+    TriggerTiming, Other{
+        Before=>"before",
+        After=>"after",
+    }
+}
+string_enum!{
+    /// How an adjustment changes its attribute
+    AdjustmentOperation, Other{
+        Set=>"set",
+        Increment=>"increment",
+    }
 }
 /// Represents an identifier (ie for parent and ancestors fields)
 #[derive(Serialize,Deserialize,PartialEq,Eq,Debug, Default,Hash,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct Identifier{
     pub id: String,
-    pub collection: String,
+    pub collection: Collection,
+    #[serde(flatten)]
+    pub extra: Extra
+}
+impl Identifier{
+    /// An identifier pointing at a creature
+    #[must_use]
+    pub fn creature(id: impl Into<String>)->Self{
+        Self{id: id.into(), collection: Collection::Creatures, extra: Extra::default()}
+    }
+    /// An identifier pointing at a creature property
+    #[must_use]
+    pub fn property(id: impl Into<String>)->Self{
+        Self{id: id.into(), collection: Collection::CreatureProperties, extra: Extra::default()}
+    }
+    /// Whether this points at a creature, ie it is the root of a property's ancestors
+    #[must_use]
+    pub fn is_creature(&self)->bool{
+        self.collection==Collection::Creatures
+    }
+    /// Whether this points at another creature property
+    #[must_use]
+    pub fn is_property(&self)->bool{
+        self.collection==Collection::CreatureProperties
+    }
 }
 /// Represents a calculated text field, such as descriptions
 /// 
@@ -270,7 +785,9 @@ pub struct CalculatedText{
     pub text: String,
     pub value: String,
     pub hash: i64,
-    pub inline_calculations: Vec<SimpleCalc>
+    pub inline_calculations: Vec<SimpleCalc>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Represents an effect on an attribute(not an effect property)
 #[derive(Serialize,Deserialize,PartialEq,Debug, Default,Clone)]
@@ -278,13 +795,15 @@ pub struct CalculatedText{
 pub struct Effect{
     #[serde(rename="_id")]
     pub id: String,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub name: Option<String>,
-    pub operation: String,
-    #[serde(default)]
-    pub amount:ValWrap,
-    #[serde(default,rename="type",skip_serializing_if="Option::is_none")]
-    pub typ: Option<String>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub name: Tracked<Option<String>>,
+    pub operation: EffectOperation,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub amount:Tracked<ValWrap>,
+    #[serde(default,rename="type",skip_serializing_if="Tracked::is_absent")]
+    pub typ: Tracked<Option<String>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Represents amunition as used by actions
 #[derive(Serialize,Deserialize,PartialEq,Debug,Default,Clone)]
@@ -292,14 +811,16 @@ pub struct Effect{
 pub struct ConsumedItem{
     #[serde(rename="_id")]
     pub id: String,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub tag: Option<String>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub quantity: Option<Calculation>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub item_id: Option<String>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub item_name: Option<String>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub tag: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub quantity: Tracked<Option<Calculation>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub item_id: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub item_name: Tracked<Option<String>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Represents a resource consumed by an action
 #[derive(Serialize,Deserialize,PartialEq,Debug,Default,Clone)]
@@ -307,14 +828,16 @@ pub struct ConsumedItem{
 pub struct ConsumedResource{
     #[serde(rename="_id")]
     pub id: String,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub variable_name: Option<String>,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    pub quantity: Option<Calculation>,
-    #[serde(default)]
-    pub available: i64,
-    #[serde(default)]
-    pub stat_name: Option<String>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub variable_name: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub quantity: Tracked<Option<Calculation>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub available: Tracked<i64>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub stat_name: Tracked<Option<String>>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Represents the `ExtraTags` field, which is used by slot like properties for tag logic
 #[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Default,Clone,Hash)]
@@ -322,8 +845,10 @@ pub struct ConsumedResource{
 pub struct ExtraTag{
     #[serde(rename="_id")]
     pub id: String,
-    pub operation: String,
-    pub tags: Vec<String>
+    pub operation: ExtraTagOperation,
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Represents the total resources consumed by an action(including attributes and items)
 #[derive(Serialize,Deserialize,PartialEq,Debug,Default,Clone)]
@@ -331,6 +856,46 @@ pub struct ExtraTag{
 pub struct Resource{
     pub items_consumed: Vec<ConsumedItem>,
     pub attributes_consumed: Vec<ConsumedResource>,
+    #[serde(flatten)]
+    pub extra: Extra
+}
+/// The components needed to cast a spell
+/// 
+/// Flattened into the spell, so these are the `verbal`, `somatic` and `material` fields
+#[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Default,Clone,Hash)]
+pub struct Components{
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub verbal: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub somatic: Tracked<bool>,
+    /// The materials needed, if any
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub material: Tracked<Option<String>>
+}
+impl Components{
+    /// Whether the spell can be cast without any components
+    #[must_use]
+    pub const fn is_empty(&self)->bool{
+        !*Tracked::get(&self.verbal) && !*Tracked::get(&self.somatic) && Tracked::get(&self.material).is_none()
+    }
+}
+impl fmt::Display for Components{
+    /// Writes the components the way spell cards do, eg `V, S, M (a bit of fleece)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+        if *self.verbal{
+            parts.push("V".to_string());
+        }
+        if *self.somatic{
+            parts.push("S".to_string());
+        }
+        match self.material.as_deref(){
+            Some("")=>parts.push("M".to_string()),
+            Some(material)=>parts.push(format!("M ({material})")),
+            None=>{}
+        }
+        f.write_str(&parts.join(", "))
+    }
 }
 /// Represents an Icon in the api
 /// 
@@ -340,60 +905,71 @@ pub struct Resource{
 pub struct Icon{
     pub name: String,
     pub shape: String,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 /// Enum that describes the type of branch for a branch property
 #[derive(Serialize,Deserialize,PartialEq,Debug,Clone)]
 #[serde(rename_all="camelCase",tag="branchType")]
 pub enum BranchType{
-    EachTarget{},
-    FailedSave{},
-    Hit{},
-    If{condition: Calculation},
-    Index{condition: Calculation},
-    Miss{},
-    SuccessfulSave{},
-    Random{}
+    EachTarget{#[serde(flatten)] extra: Extra},
+    FailedSave{#[serde(flatten)] extra: Extra},
+    Hit{#[serde(flatten)] extra: Extra},
+    If{condition: Calculation,
+        #[serde(flatten)] extra: Extra},
+    Index{condition: Calculation,
+        #[serde(flatten)] extra: Extra},
+    Miss{#[serde(flatten)] extra: Extra},
+    SuccessfulSave{#[serde(flatten)] extra: Extra},
+    Random{#[serde(flatten)] extra: Extra}
 }
 /// Represents a row for a point buy property
-#[derive(Serialize,Deserialize,PartialEq,Debug,Default,Clone)]
+#[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Default,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct PointBuyRow{
     #[serde(rename="_id")]
     id: String,
-    #[serde(default)]
-    name: String,
-    #[serde(default,skip_serializing_if="Option::is_none")]
-    variable_name: Option<String>,
-    #[serde(default)] value: i64,
-    #[serde(default)] spent: i64,
-    #[serde(default)] errors: Vec<ParseError>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    name: Tracked<String>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    variable_name: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] value: Tracked<i64>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] spent: Tracked<i64>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] errors: Tracked<Vec<ParseError>>,
+    #[serde(flatten)]
+    extra: Extra
 }
 /// Enum describes the types of attributes and assosiated properties
 #[derive(Serialize,Deserialize,PartialEq,Debug,Clone)]
 #[serde(rename_all="camelCase",tag="attributeType")]
+#[allow(clippy::large_enum_variant)]
 pub enum AttributeType{
-    Ability{#[serde(default)] modifier: i64,
-        #[serde(default,skip_serializing_if="Option::is_none")] proficiency: Option<f64>},
-    HealthBar{#[serde(rename="healthBarColorMid",default,skip_serializing_if="Option::is_none")]
-        health_bar_color_mid: Option<String>,
-        #[serde(rename="healthBarNoDamage",default)] health_bar_no_damage: bool,
-        #[serde(rename="healthBarNoHealing",default)] health_bar_no_healing: bool,
-        #[serde(rename="healthBarNoDamageOverflow",default)] health_bar_no_damage_overflow: bool,
-        #[serde(rename="healthBarNoHealingOverflow",default)] health_bar_no_healing_overflow: bool,
-        #[serde(rename="healthBarDamageOrder",default,skip_serializing_if="Option::is_none")] health_bar_damage_order: Option<i64>,
-        #[serde(rename="healthBarHealingOrder",default,skip_serializing_if="Option::is_none")] health_bar_healing_order: Option<i64>,
+    Ability{#[serde(default,skip_serializing_if="Tracked::is_absent")] modifier: Tracked<i64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_optional_number")] proficiency: Tracked<Option<f64>>,
+        #[serde(flatten)] extra: Extra},
+    HealthBar{#[serde(rename="healthBarColorMid",default,skip_serializing_if="Tracked::is_absent")]
+        health_bar_color_mid: Tracked<Option<String>>,
+        #[serde(rename="healthBarNoDamage",default,skip_serializing_if="Tracked::is_absent")] health_bar_no_damage: Tracked<bool>,
+        #[serde(rename="healthBarNoHealing",default,skip_serializing_if="Tracked::is_absent")] health_bar_no_healing: Tracked<bool>,
+        #[serde(rename="healthBarNoDamageOverflow",default,skip_serializing_if="Tracked::is_absent")] health_bar_no_damage_overflow: Tracked<bool>,
+        #[serde(rename="healthBarNoHealingOverflow",default,skip_serializing_if="Tracked::is_absent")] health_bar_no_healing_overflow: Tracked<bool>,
+        #[serde(rename="healthBarDamageOrder",default,skip_serializing_if="Tracked::is_absent")] health_bar_damage_order: Tracked<Option<i64>>,
+        #[serde(rename="healthBarHealingOrder",default,skip_serializing_if="Tracked::is_absent")] health_bar_healing_order: Tracked<Option<i64>>,
+        #[serde(flatten)] extra: Extra
     },
     HitDice{#[serde(rename="hitDiceSize")] hit_dice_size: String,
-        #[serde(rename="constitutionMod",default)] constitution_mod: i64},
-    Modifier{},
-    Resource{},
-    Stat{},
-    SpellSlot{#[serde(rename="spellSlotLevel",default,skip_serializing_if="Option::is_none")] spell_slot_level: Option<Calculation>},
-    Utility{}   
+        #[serde(rename="constitutionMod",default,skip_serializing_if="Tracked::is_absent")] constitution_mod: Tracked<i64>,
+        #[serde(flatten)] extra: Extra},
+    Modifier{#[serde(flatten)] extra: Extra},
+    Resource{#[serde(flatten)] extra: Extra},
+    Stat{#[serde(flatten)] extra: Extra},
+    SpellSlot{#[serde(rename="spellSlotLevel",default,skip_serializing_if="Tracked::is_absent")] spell_slot_level: Tracked<Option<Calculation>>,
+        #[serde(flatten)] extra: Extra},
+    Utility{#[serde(flatten)] extra: Extra}   
 }
 impl Default for AttributeType{
     fn default() -> Self {
-        Self::Utility {}
+        Self::Utility {extra: Extra::default()}
     }
 }
 /// Represents a cache for errors on ref properties
@@ -401,265 +977,474 @@ impl Default for AttributeType{
 /// Don't worry about this
 #[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Default,Clone,Hash)]
 pub struct Cache{
-    pub error: String
+    pub error: String,
+    #[serde(flatten)]
+    pub extra: Extra
 }
 #[derive(Serialize,Deserialize,PartialEq,Debug,Clone)]
 #[serde(rename_all="camelCase",tag="type")]
 /// an enum that encodes the various property types
 /// 
 pub enum PropType{
-    Action{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")]summary: Option<CalculatedText>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="actionType")] action_type: String, 
-        #[serde(default,rename="variableName",skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        target: String,
-        #[serde(default,rename="attackRoll",skip_serializing_if="Option::is_none")] attack_roll: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] uses: Option<Calculation>,
-        #[serde(default,rename="usesUsed")] uses_used: i64,
-        #[serde(default,skip_serializing_if="Option::is_none")] reset: Option<String>,
-        #[serde(default)] silent: bool,
+    Action{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")]summary: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="actionType")] action_type: ActionType, 
+        #[serde(default,rename="variableName",skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        target: Target,
+        #[serde(default,rename="attackRoll",skip_serializing_if="Tracked::is_absent")] attack_roll: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] uses: Tracked<Option<Calculation>>,
+        #[serde(default,rename="usesUsed",skip_serializing_if="Tracked::is_absent")] uses_used: Tracked<i64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] reset: Tracked<Option<Reset>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
         resources: Resource,
-        #[serde(default,rename="insufficientResources")] insufficient_resources: bool,
-        #[serde(default,rename="usesLeft",skip_serializing_if="Option::is_none")] uses_left: Option<i64>,
-        #[serde(default)] overridden: bool},
-    Adjustment{#[serde(default,skip_serializing_if="Option::is_none")] amount: Option<Calculation>,
-        target: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] stat: Option<String>, operation: String,
-        #[serde(default)] silent:bool},//attribute damage for some reason
-    Attribute{#[serde(default)] name: String, #[serde(rename="variableName")] variable_name: String, 
+        #[serde(default,rename="insufficientResources",skip_serializing_if="Tracked::is_absent")] insufficient_resources: Tracked<bool>,
+        #[serde(default,rename="usesLeft",skip_serializing_if="Tracked::is_absent")] uses_left: Tracked<Option<i64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Adjustment{#[serde(default,skip_serializing_if="Tracked::is_absent")] amount: Tracked<Option<Calculation>>,
+        target: Target,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] stat: Tracked<Option<String>>, operation: AdjustmentOperation,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent:Tracked<bool>,
+        #[serde(flatten)] extra: Extra},//attribute damage for some reason
+    Attribute{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(rename="variableName")] variable_name: String, 
         #[serde(flatten)] attribute_type: AttributeType,
-        #[serde(rename="baseValue",default,skip_serializing_if="Option::is_none")] base_value: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(default)] damage: i64,
-        #[serde(default)] decimal: bool,
-        #[serde(rename="ignoreLowerLimit",default)] ignore_lower_limit: bool,
-        #[serde(rename="ignoreUpperLimit",default)] ignore_upper_limit: bool,
-        #[serde(rename="hideWhenValueZero",default)] hide_when_value_zero: bool,
-        #[serde(rename="hideWhenTotalZero",default)] hide_when_total_zero: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] reset:Option<String>,
-        #[serde(default)] total: PropVal,
-        #[serde(default)] value: PropVal,#[serde(default)] effects: Vec<Effect>,
-        #[serde(default)] hide: bool, #[serde(default)] overridden: bool},
+        #[serde(rename="baseValue",default,skip_serializing_if="Tracked::is_absent")] base_value: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] damage: Tracked<i64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] decimal: Tracked<bool>,
+        #[serde(rename="ignoreLowerLimit",default,skip_serializing_if="Tracked::is_absent")] ignore_lower_limit: Tracked<bool>,
+        #[serde(rename="ignoreUpperLimit",default,skip_serializing_if="Tracked::is_absent")] ignore_upper_limit: Tracked<bool>,
+        #[serde(rename="hideWhenValueZero",default,skip_serializing_if="Tracked::is_absent")] hide_when_value_zero: Tracked<bool>,
+        #[serde(rename="hideWhenTotalZero",default,skip_serializing_if="Tracked::is_absent")] hide_when_total_zero: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] reset: Tracked<Option<Reset>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] total: Tracked<PropVal>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] value: Tracked<PropVal>,#[serde(default,skip_serializing_if="Tracked::is_absent")] effects: Tracked<Vec<Effect>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] hide: Tracked<bool>, #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>},
     Branch{#[serde(flatten)] branch_type: BranchType,
-        #[serde(default,skip_serializing_if="Option::is_none")] text: Option<String>,
-        #[serde(default)] silent: bool},
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] text: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>},
     Buff{name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="hideRemoveButton",default)]hide_remove_button:bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] duration: Option<Calculation>,
-        target: String,
-        #[serde(default)] silent:bool,
-        #[serde(rename="skipCrystalization",default)]skip_crystalization: bool,
-        #[serde(rename="appliedBy",default,skip_serializing_if="Option::is_none")] applied_by: Option<String>},
-    BuffRemover{#[serde(default)] name:String,
-        #[serde(rename="targetParentBuff",default)]target_parent_buff:bool,
-        target: String, #[serde(rename="removeAll",default)]remove_all:bool,
-        #[serde(default,rename="targetTags")] target_tags: Vec<String>,
-        #[serde(default,rename="extraTags")] extra_tags: Vec<ExtraTag>,
-        #[serde(default)]silent:bool},
-    Class{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="variableName",default,skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        #[serde(default,rename="slotTags")] slot_tags: Vec<String>,
-        #[serde(rename="extraTags",default)] extra_tags: Vec<ExtraTag>,
-        #[serde(rename="slotCondition",default,skip_serializing_if="Option::is_none")] slot_condition: Option<Calculation>,
-        #[serde(default)] level: i64,
-        #[serde(rename="missingLevels",default)] missing_levels: Vec<i64>},
-    ClassLevel{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="variableName",default,skip_serializing_if="Option::is_none")] variable_name: Option<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="hideRemoveButton",default,skip_serializing_if="Tracked::is_absent")]hide_remove_button:Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] duration: Tracked<Option<Calculation>>,
+        target: Target,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent:Tracked<bool>,
+        #[serde(rename="skipCrystalization",default,skip_serializing_if="Tracked::is_absent")]skip_crystalization: Tracked<bool>,
+        #[serde(rename="appliedBy",default,skip_serializing_if="Tracked::is_absent")] applied_by: Tracked<Option<String>>,
+        #[serde(flatten)] extra: Extra},
+    BuffRemover{#[serde(default,skip_serializing_if="Tracked::is_absent")] name:Tracked<String>,
+        #[serde(rename="targetParentBuff",default,skip_serializing_if="Tracked::is_absent")]target_parent_buff:Tracked<bool>,
+        target: Target, #[serde(rename="removeAll",default,skip_serializing_if="Tracked::is_absent")]remove_all:Tracked<bool>,
+        #[serde(default,rename="targetTags",skip_serializing_if="Tracked::is_absent")] target_tags: Tracked<Vec<String>>,
+        #[serde(default,rename="extraTags",skip_serializing_if="Tracked::is_absent")] extra_tags: Tracked<Vec<ExtraTag>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")]silent:Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Class{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="variableName",default,skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        #[serde(default,rename="slotTags",skip_serializing_if="Tracked::is_absent")] slot_tags: Tracked<Vec<String>>,
+        #[serde(rename="extraTags",default,skip_serializing_if="Tracked::is_absent")] extra_tags: Tracked<Vec<ExtraTag>>,
+        #[serde(rename="slotCondition",default,skip_serializing_if="Tracked::is_absent")] slot_condition: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] level: Tracked<i64>,
+        #[serde(rename="missingLevels",default,skip_serializing_if="Tracked::is_absent")] missing_levels: Tracked<Vec<i64>>,
+        #[serde(flatten)] extra: Extra},
+    ClassLevel{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="variableName",default,skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
         level: i64,
-        #[serde(rename="slotFillerCondition",default,skip_serializing_if="Option::is_none")] slot_filler_condition: Option<Calculation>},
-    Constant{#[serde(default)]name: String,
-        #[serde(rename="variableName",default,skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        #[serde(default,skip_serializing_if="Option::is_none")] calculation: Option<String>,
-        errors: Vec<ParseError>},
-    Container{#[serde(default)] name: String, #[serde(default)] carried: bool,
-        #[serde(rename="contentsWeightless",default)] contents_weightless: bool,
-        #[serde(default)] weight: f64, #[serde(default)] value: f64,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="contentsWeight",default)] contents_weight: f64,
-        #[serde(rename="carriedWeight",default)] carried_weight: f64,
-        #[serde(rename="contentsValue",default)] contents_value: f64,
-        #[serde(rename="carriedValue",default)] carried_value: f64},
-    Damage{#[serde(default)] amount: Option<Calculation>,
-        target: String, #[serde(rename="damageType")] damage_type: String,
-        #[serde(default)] silent: bool},
-    DamageMultiplier{#[serde(default)] name: String,
-        #[serde(rename="damageTypes")] damage_types: Vec<String>,value: f64,
-        #[serde(rename="excludeTags",default)] exclude_tags: Vec<String>,
-        #[serde(rename="includeTags",default)] include_tags: Vec<String>},
-    Effect{#[serde(default)] name: String, operation: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] amount: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] text: Option<String>,
+        #[serde(rename="slotFillerCondition",default,skip_serializing_if="Tracked::is_absent")] slot_filler_condition: Tracked<Option<Calculation>>,
+        #[serde(flatten)] extra: Extra},
+    Constant{#[serde(default,skip_serializing_if="Tracked::is_absent")]name: Tracked<String>,
+        #[serde(rename="variableName",default,skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] calculation: Tracked<Option<String>>,
+        errors: Vec<ParseError>,
+        #[serde(flatten)] extra: Extra},
+    Container{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(default,skip_serializing_if="Tracked::is_absent")] carried: Tracked<bool>,
+        #[serde(rename="contentsWeightless",default,skip_serializing_if="Tracked::is_absent")] contents_weightless: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] weight: Tracked<f64>, #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] value: Tracked<f64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="contentsWeight",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] contents_weight: Tracked<f64>,
+        #[serde(rename="carriedWeight",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] carried_weight: Tracked<f64>,
+        #[serde(rename="contentsValue",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] contents_value: Tracked<f64>,
+        #[serde(rename="carriedValue",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] carried_value: Tracked<f64>,
+        #[serde(flatten)] extra: Extra},
+    Damage{#[serde(default,skip_serializing_if="Tracked::is_absent")] amount: Tracked<Option<Calculation>>,
+        target: Target, #[serde(rename="damageType")] damage_type: DamageType,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    DamageMultiplier{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(rename="damageTypes")] damage_types: Vec<DamageType>,#[serde(serialize_with="serialize_number")] value: f64,
+        #[serde(rename="excludeTags",default,skip_serializing_if="Tracked::is_absent")] exclude_tags: Tracked<Vec<String>>,
+        #[serde(rename="includeTags",default,skip_serializing_if="Tracked::is_absent")] include_tags: Tracked<Vec<String>>,
+        #[serde(flatten)] extra: Extra},
+    Effect{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, operation: EffectOperation,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] amount: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] text: Tracked<Option<String>>,
         stats: Vec<String>,
-        #[serde(rename="targetByTags",default)] target_by_tags: bool,
-        #[serde(rename="targetField",default,skip_serializing_if="Option::is_none")] target_field: Option<String>,
-        #[serde(rename="targetTags",default,skip_serializing_if="Option::is_none")] target_tags: Option<Vec<String>>,
-        #[serde(rename="extraTags",default)] extra_tags: Vec<ExtraTag>},
-    Feature{#[serde(default)] name: String, #[serde(default)] sumary: Option<CalculatedText>,
-        #[serde(default)] description: Option<CalculatedText>},
-    Folder{#[serde(default)] name: String, #[serde(rename="groupStats",default)] group_stats: bool,
-        #[serde(rename="hideStatsGroup",default,skip_serializing_if="Option::is_none")] hide_stats_group: Option<bool>,
-        #[serde(default,skip_serializing_if="Option::is_none")] tab: Option<String>,
-        #[serde(default,skip_serializing_if="Option::is_none")] location: Option<String>},
-    Item{#[serde(default)] name: String, #[serde(default)] plural: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
+        #[serde(rename="targetByTags",default,skip_serializing_if="Tracked::is_absent")] target_by_tags: Tracked<bool>,
+        #[serde(rename="targetField",default,skip_serializing_if="Tracked::is_absent")] target_field: Tracked<Option<String>>,
+        #[serde(rename="targetTags",default,skip_serializing_if="Tracked::is_absent")] target_tags: Tracked<Option<Vec<String>>>,
+        #[serde(rename="extraTags",default,skip_serializing_if="Tracked::is_absent")] extra_tags: Tracked<Vec<ExtraTag>>,
+        #[serde(flatten)] extra: Extra},
+    Feature{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(default,skip_serializing_if="Tracked::is_absent")] sumary: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(flatten)] extra: Extra},
+    Folder{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(rename="groupStats",default,skip_serializing_if="Tracked::is_absent")] group_stats: Tracked<bool>,
+        #[serde(rename="hideStatsGroup",default,skip_serializing_if="Tracked::is_absent")] hide_stats_group: Tracked<Option<bool>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] tab: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] location: Tracked<Option<String>>,
+        #[serde(flatten)] extra: Extra},
+    Item{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(default,skip_serializing_if="Tracked::is_absent")] plural: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
         quantity: i64,
-        #[serde(default)] weight: f64,#[serde(default)] value: f64,
-        #[serde(rename="requiresAttunement",default)] requires_attunement: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] attuned: Option<bool>,
-        #[serde(rename="showIncrement",default)] show_increment: bool, equipped: bool},
-    Note{#[serde(default)] name: String, 
-        #[serde(default,skip_serializing_if="Option::is_none")] summary: Option<CalculatedText>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>},
-    PointBuy{#[serde(default)] name: String,#[serde(default)] ignored: bool, values: Vec<PointBuyRow>,
-        #[serde(default,skip_serializing_if="Option::is_none")] min: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] max: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] total: Option<Calculation>,
-        cost: Calculation,#[serde(default)] spent: i64,#[serde(rename="pointsLeft")] points_left: i64,
-        #[serde(default)]errors: Vec<ParseError>},
-    Proficiency{#[serde(default)] name: String,stats: Vec<String>,value: f64},
-    PropertySlot{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="slotType",default,skip_serializing_if="Option::is_none")] slot_type: Option<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] weight: Tracked<f64>,#[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_number")] value: Tracked<f64>,
+        #[serde(rename="requiresAttunement",default,skip_serializing_if="Tracked::is_absent")] requires_attunement: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] attuned: Tracked<Option<bool>>,
+        #[serde(rename="showIncrement",default,skip_serializing_if="Tracked::is_absent")] show_increment: Tracked<bool>, equipped: bool,
+        #[serde(flatten)] extra: Extra},
+    Note{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, 
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] summary: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(flatten)] extra: Extra},
+    PointBuy{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,#[serde(default,skip_serializing_if="Tracked::is_absent")] ignored: Tracked<bool>, values: Vec<PointBuyRow>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] min: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] max: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] total: Tracked<Option<Calculation>>,
+        cost: Calculation,#[serde(default,skip_serializing_if="Tracked::is_absent")] spent: Tracked<i64>,#[serde(rename="pointsLeft")] points_left: i64,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")]errors: Tracked<Vec<ParseError>>,
+        #[serde(flatten)] extra: Extra},
+    Proficiency{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,stats: Vec<String>,#[serde(serialize_with="serialize_number")] value: f64,
+        #[serde(flatten)] extra: Extra},
+    PropertySlot{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="slotType",default,skip_serializing_if="Tracked::is_absent")] slot_type: Tracked<Option<String>>,
         #[serde(rename="slotTags")] slot_tags: Vec<String>,
         #[serde(rename="extraTags")] extra_tags: Vec<ExtraTag>,
-        #[serde(rename="quantityExpected",default,skip_serializing_if="Option::is_none")] quantity_expected: Option<Calculation>,
-        #[serde(default)] ignored: bool,
-        #[serde(rename="slotCondition",default,skip_serializing_if="Option::is_none")] slot_condition: Option<Calculation>,
+        #[serde(rename="quantityExpected",default,skip_serializing_if="Tracked::is_absent")] quantity_expected: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] ignored: Tracked<bool>,
+        #[serde(rename="slotCondition",default,skip_serializing_if="Tracked::is_absent")] slot_condition: Tracked<Option<Calculation>>,
         #[serde(rename="hideWhenFull")] hide_when_full: bool,unique: String,
-        #[serde(rename="totalFilled",default)] total_filled: i64,
-        #[serde(rename="spaceLeft",default,skip_serializing_if="Option::is_none")] space_left: Option<i64>},
-    Reference{#[serde(rename="ref")] refer: Identifier,cache: Cache},
-    Roll{#[serde(default)] name: String, #[serde(rename="variableName")] variable_name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] roll: Option<Calculation>,
-        #[serde(default)] silent: bool},
-    SavingThrow{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] dc: Option<Calculation>,
-        target: String, #[serde(default,skip_serializing_if="Option::is_none")] stat: Option<String>,
-        #[serde(default)] silent: bool},
-    Skill{#[serde(default)] name: String,
-        #[serde(rename="variableName",default,skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        #[serde(default,skip_serializing_if="Option::is_none")] ability: Option<String>,
+        #[serde(rename="totalFilled",default,skip_serializing_if="Tracked::is_absent")] total_filled: Tracked<i64>,
+        #[serde(rename="spaceLeft",default,skip_serializing_if="Tracked::is_absent")] space_left: Tracked<Option<i64>>,
+        #[serde(flatten)] extra: Extra},
+    Reference{#[serde(rename="ref")] refer: Identifier,cache: Cache,
+        #[serde(flatten)] extra: Extra},
+    Roll{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>, #[serde(rename="variableName")] variable_name: String,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] roll: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    SavingThrow{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] dc: Tracked<Option<Calculation>>,
+        target: Target, #[serde(default,skip_serializing_if="Tracked::is_absent")] stat: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Skill{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(rename="variableName",default,skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] ability: Tracked<Option<String>>,
         #[serde(rename="skillType")] skill_type: String,
-        #[serde(rename="baseProficiency",default,skip_serializing_if="Option::is_none")] base_proficiency: Option<f64>,
-        #[serde(rename="baseValue",default,skip_serializing_if="Option::is_none")] base_value: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
+        #[serde(rename="baseProficiency",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_optional_number")] base_proficiency: Tracked<Option<f64>>,
+        #[serde(rename="baseValue",default,skip_serializing_if="Tracked::is_absent")] base_value: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
         value: i64,
-        #[serde(rename="abilityMod",default)] ability_mod: i64,
-        #[serde(default)] advantage: i64, #[serde(default,rename="passiveBonus")] passive_bonus: i64,
-        proficiency: f64,
-        #[serde(default,rename="conditionalBenefits")] conditional_benifits: Vec<String>,
-        #[serde(default)] fail: i64, #[serde(default)] hide:bool, #[serde(default)] overridden: bool,
-        #[serde(default)] effects: Vec<Effect>
+        #[serde(rename="abilityMod",default,skip_serializing_if="Tracked::is_absent")] ability_mod: Tracked<i64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] advantage: Tracked<i64>, #[serde(default,rename="passiveBonus",skip_serializing_if="Tracked::is_absent")] passive_bonus: Tracked<i64>,
+        #[serde(serialize_with="serialize_number")] proficiency: f64,
+        #[serde(default,rename="conditionalBenefits",skip_serializing_if="Tracked::is_absent")] conditional_benifits: Tracked<Vec<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] fail: Tracked<i64>, #[serde(default,skip_serializing_if="Tracked::is_absent")] hide:Tracked<bool>, #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] effects: Tracked<Vec<Effect>>,
+        #[serde(flatten)] extra: Extra
         },
-    SlotFiller{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] picture: Option<String>,
-        #[serde(default)] description: String, 
-        #[serde(rename="slotFillerType",default,skip_serializing_if="Option::is_none")] slot_filler_type: Option<String>,
+    SlotFiller{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] picture: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<String>, 
+        #[serde(rename="slotFillerType",default,skip_serializing_if="Tracked::is_absent")] slot_filler_type: Tracked<Option<String>>,
         #[serde(rename="slotQuantityFilled")] slot_quantity_filled: i64,
-        #[serde(rename="slotFillerCondition",default,skip_serializing_if="Option::is_none")] slot_filler_condition: Option<String>},
-    SpellList{#[serde(default)] name: String,
-        #[serde(rename="maxPrepared",default,skip_serializing_if="Option::is_none")]max_prepared: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] dc: Option<Calculation>,
-        #[serde(rename="attackRollBonus",default,skip_serializing_if="Option::is_none")] attack_roll_bonus: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
+        #[serde(rename="slotFillerCondition",default,skip_serializing_if="Tracked::is_absent")] slot_filler_condition: Tracked<Option<String>>,
+        #[serde(flatten)] extra: Extra},
+    SpellList{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(rename="maxPrepared",default,skip_serializing_if="Tracked::is_absent")]max_prepared: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] dc: Tracked<Option<Calculation>>,
+        #[serde(rename="attackRollBonus",default,skip_serializing_if="Tracked::is_absent")] attack_roll_bonus: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
         ability: String,
-        #[serde(rename="abilityMod",default)] ability_mod: i64},
-    Spell{#[serde(default)] name: String,
-        #[serde(rename="alwaysPrepared",default)] always_prepared: bool,
-        #[serde(default)] prepared: bool,
-        #[serde(rename="castWithoutSpellSlots",default)] cast_without_spell_slots: bool,
-        #[serde(rename="hasAttackRoll",default)] has_attack_roll: bool,
-        #[serde(rename="castingTime",default,skip_serializing_if="Option::is_none")] casting_time: Option<String>,
-        #[serde(default,skip_serializing_if="Option::is_none")] range: Option<String>,
-        #[serde(default,skip_serializing_if="Option::is_none")] duration: Option<String>,
-        #[serde(default)] verbal: bool, #[serde(default)] somatic: bool,
-        #[serde(default)] concentration: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] material: Option<String>,
-        #[serde(default)] ritual: bool, level: i64, school: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] summary: Option<CalculatedText>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(rename="actionType")] action_type: String, 
-        #[serde(default,rename="variableName",skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        target: String,
-        #[serde(default,rename="attackRoll",skip_serializing_if="Option::is_none")] attack_roll: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] uses: Option<Calculation>,
-        #[serde(default,rename="usesUsed")] uses_used: Option<i64>,
-        #[serde(default,skip_serializing_if="Option::is_none")] reset: Option<String>,
-        #[serde(default)] silent: bool,
+        #[serde(rename="abilityMod",default,skip_serializing_if="Tracked::is_absent")] ability_mod: Tracked<i64>,
+        #[serde(flatten)] extra: Extra},
+    Spell{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(rename="alwaysPrepared",default,skip_serializing_if="Tracked::is_absent")] always_prepared: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] prepared: Tracked<bool>,
+        #[serde(rename="castWithoutSpellSlots",default,skip_serializing_if="Tracked::is_absent")] cast_without_spell_slots: Tracked<bool>,
+        #[serde(rename="hasAttackRoll",default,skip_serializing_if="Tracked::is_absent")] has_attack_roll: Tracked<bool>,
+        #[serde(rename="castingTime",default,skip_serializing_if="Tracked::is_absent")] casting_time: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] range: Tracked<Option<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] duration: Tracked<Option<String>>,
+        #[serde(flatten)] components: Components,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] concentration: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] ritual: Tracked<bool>, level: i64, school: SpellSchool,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] summary: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(rename="actionType")] action_type: ActionType, 
+        #[serde(default,rename="variableName",skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        target: Target,
+        #[serde(default,rename="attackRoll",skip_serializing_if="Tracked::is_absent")] attack_roll: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] uses: Tracked<Option<Calculation>>,
+        #[serde(default,rename="usesUsed",skip_serializing_if="Tracked::is_absent")] uses_used: Tracked<Option<i64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] reset: Tracked<Option<Reset>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
         resources: Resource,
-        #[serde(default,rename="insufficientResources")] insufficient_resources: bool,
-        #[serde(default,rename="usesLeft",skip_serializing_if="Option::is_none")] uses_left: Option<i64>,
-        #[serde(default)] overridden: bool,
-        #[serde(rename="deactivatedBySelf",default)] deactivated_by_self: bool},
-    Toggle{#[serde(default)] name: String,
-        #[serde(default,rename="variableName",skip_serializing_if="Option::is_none")] variable_name: Option<String>,
-        #[serde(rename="showUI",default)] show_ui: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] disabled: Option<bool>,
-        #[serde(default,skip_serializing_if="Option::is_none")] enabled: Option<bool>,
-        #[serde(default,skip_serializing_if="Option::is_none")] condition: Option<Calculation>,
-        #[serde(rename="deactivatedBySelf",default)] deactivated_by_self: bool},
-    Trigger{#[serde(default)] name: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        event: String,
-        #[serde(rename="actionPropertyType",default,skip_serializing_if="Option::is_none")] action_property_type: Option<String>,
-        timing: String,
-        #[serde(default,skip_serializing_if="Option::is_none")] condition: Option<Calculation>,
-        #[serde(rename="targetTags",default)] target_tags: Vec<String>, 
-        #[serde(rename="extraTags",default)] extra_tags: Vec<ExtraTag>,
-        #[serde(default)] silent: bool}
+        #[serde(default,rename="insufficientResources",skip_serializing_if="Tracked::is_absent")] insufficient_resources: Tracked<bool>,
+        #[serde(default,rename="usesLeft",skip_serializing_if="Tracked::is_absent")] uses_left: Tracked<Option<i64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>,
+        #[serde(rename="deactivatedBySelf",default,skip_serializing_if="Tracked::is_absent")] deactivated_by_self: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Toggle{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,rename="variableName",skip_serializing_if="Tracked::is_absent")] variable_name: Tracked<Option<String>>,
+        #[serde(rename="showUI",default,skip_serializing_if="Tracked::is_absent")] show_ui: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] disabled: Tracked<Option<bool>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] enabled: Tracked<Option<bool>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] condition: Tracked<Option<Calculation>>,
+        #[serde(rename="deactivatedBySelf",default,skip_serializing_if="Tracked::is_absent")] deactivated_by_self: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Trigger{#[serde(default,skip_serializing_if="Tracked::is_absent")] name: Tracked<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        event: TriggerEvent,
+        #[serde(rename="actionPropertyType",default,skip_serializing_if="Tracked::is_absent")] action_property_type: Tracked<Option<String>>,
+        timing: TriggerTiming,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] condition: Tracked<Option<Calculation>>,
+        #[serde(rename="targetTags",default,skip_serializing_if="Tracked::is_absent")] target_tags: Tracked<Vec<String>>, 
+        #[serde(rename="extraTags",default,skip_serializing_if="Tracked::is_absent")] extra_tags: Tracked<Vec<ExtraTag>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] silent: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    /// A property that couldn't be read, kept as the raw json so it can be written back out unchanged
+    /// 
+    /// Only produced by lenient imports, see [`FlatCharacter::from_json_lenient`][crate::FlatCharacter::from_json_lenient].
+    /// `typ` is the `type` it was read with, if that was a string, and every other field of the type is kept in `extra`
+    #[serde(skip)]
+    Unknown{typ: Option<String>, error: String, extra: Extra}
+}
+/// How an unknown property type is written, its `type` followed by the rest of its fields
+#[derive(Serialize)]
+struct UnknownProp<'a>{
+    #[serde(rename="type",skip_serializing_if="Option::is_none")]
+    typ: &'a Option<String>,
+    #[serde(flatten)]
+    extra: &'a Extra
+}
+/// Serializes a flattened `PropType`, writing unknown properties back out as the fields they were read with
+pub(crate) fn serialize_prop_type<S: Serializer>(prop_type: &PropType, serializer: S)->Result<S::Ok,S::Error>{
+    match prop_type{
+        PropType::Unknown{typ,extra,..}=>UnknownProp{typ,extra}.serialize(serializer),
+        _=>prop_type.serialize(serializer)
+    }
 }
 impl Default for PropType{
     fn default() -> Self {
-        Self::Folder { name: String::default(), group_stats: false, hide_stats_group: None, location: None, tab: None }
+        Self::Folder { name: Tracked::default(), group_stats: Tracked::default(), hide_stats_group: Tracked::default(), location: Tracked::default(), tab: Tracked::default(), extra: Extra::default() }
+    }
+}
+impl PropType{
+    /// The `type` dicecloud gives this property, eg `"attribute"` or `"spellList"`
+    /// 
+    /// Unknown properties give whatever type they were read with, or `""` if they didn't have one
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn type_name(&self)->&str{
+        match self{
+            Self::Unknown{typ,..}=>typ.as_deref().unwrap_or_default(),
+            _=>self.kind().known_str().unwrap_or_default()
+        }
+    }
+    /// Which type of property this is, without any of its fields
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn kind(&self)->PropKind{
+        match self{
+            Self::Action{..}=>PropKind::Action,
+            Self::Adjustment{..}=>PropKind::Adjustment,
+            Self::Attribute{..}=>PropKind::Attribute,
+            Self::Branch{..}=>PropKind::Branch,
+            Self::Buff{..}=>PropKind::Buff,
+            Self::BuffRemover{..}=>PropKind::BuffRemover,
+            Self::Class{..}=>PropKind::Class,
+            Self::ClassLevel{..}=>PropKind::ClassLevel,
+            Self::Constant{..}=>PropKind::Constant,
+            Self::Container{..}=>PropKind::Container,
+            Self::Damage{..}=>PropKind::Damage,
+            Self::DamageMultiplier{..}=>PropKind::DamageMultiplier,
+            Self::Effect{..}=>PropKind::Effect,
+            Self::Feature{..}=>PropKind::Feature,
+            Self::Folder{..}=>PropKind::Folder,
+            Self::Item{..}=>PropKind::Item,
+            Self::Note{..}=>PropKind::Note,
+            Self::PointBuy{..}=>PropKind::PointBuy,
+            Self::Proficiency{..}=>PropKind::Proficiency,
+            Self::PropertySlot{..}=>PropKind::PropertySlot,
+            Self::Reference{..}=>PropKind::Reference,
+            Self::Roll{..}=>PropKind::Roll,
+            Self::SavingThrow{..}=>PropKind::SavingThrow,
+            Self::Skill{..}=>PropKind::Skill,
+            Self::SlotFiller{..}=>PropKind::SlotFiller,
+            Self::SpellList{..}=>PropKind::SpellList,
+            Self::Spell{..}=>PropKind::Spell,
+            Self::Toggle{..}=>PropKind::Toggle,
+            Self::Trigger{..}=>PropKind::Trigger,
+            Self::Unknown{..}=>PropKind::Unknown(self.type_name().to_string())
+        }
+    }
+    /// The name shown for the property, if its type has one
+    #[must_use]
+    pub fn name(&self)->Option<&str>{
+        match self{
+            Self::Buff{name,..}=>Some(name),
+            Self::Action{name,..}|Self::Attribute{name,..}|Self::BuffRemover{name,..}|Self::Class{name,..}|Self::ClassLevel{name,..}
+                |Self::Constant{name,..}|Self::Container{name,..}|Self::DamageMultiplier{name,..}|Self::Effect{name,..}|Self::Feature{name,..}|Self::Folder{name,..}
+                |Self::Item{name,..}|Self::Note{name,..}|Self::PointBuy{name,..}|Self::Proficiency{name,..}|Self::PropertySlot{name,..}|Self::Roll{name,..}
+                |Self::SavingThrow{name,..}|Self::Skill{name,..}|Self::SlotFiller{name,..}|Self::SpellList{name,..}|Self::Spell{name,..}|Self::Toggle{name,..}
+                |Self::Trigger{name,..}=>Some(name),
+            _=>None
+        }
+    }
+    /// The variable name other properties can refer to this one by in calculations, if it has one
+    #[must_use]
+    pub fn variable_name(&self)->Option<&str>{
+        match self{
+            Self::Attribute{variable_name,..}|Self::Roll{variable_name,..}=>Some(variable_name),
+            Self::Action{variable_name,..}|Self::Class{variable_name,..}|Self::ClassLevel{variable_name,..}|Self::Constant{variable_name,..}
+                |Self::Skill{variable_name,..}|Self::Spell{variable_name,..}|Self::Toggle{variable_name,..}=>variable_name.as_deref(),
+            _=>None
+        }
+    }
+    /// The description of the property, if its type has one
+    /// 
+    /// Slot fillers have a plain text description rather than a calculated one, so aren't included
+    #[must_use]
+    pub const fn description(&self)->Option<&CalculatedText>{
+        match self{
+            Self::Action{description,..}|Self::Attribute{description,..}|Self::Buff{description,..}|Self::Class{description,..}|Self::ClassLevel{description,..}
+                |Self::Container{description,..}|Self::Feature{description,..}|Self::Item{description,..}|Self::Note{description,..}|Self::PropertySlot{description,..}
+                |Self::Skill{description,..}|Self::SpellList{description,..}|Self::Spell{description,..}|Self::Trigger{description,..}=>Tracked::get(description).as_ref(),
+            _=>None
+        }
+    }
+    /// The summary of the property, if its type has one
+    #[must_use]
+    pub const fn summary(&self)->Option<&CalculatedText>{
+        match self{
+            Self::Action{summary,..}|Self::Note{summary,..}|Self::Spell{summary,..}|Self::Feature{sumary: summary,..}=>Tracked::get(summary).as_ref(),
+            _=>None
+        }
+    }
+    /// Who the property applies to, if its type has one
+    #[must_use]
+    pub const fn target(&self)->Option<&Target>{
+        match self{
+            Self::Action{target,..}|Self::Adjustment{target,..}|Self::Buff{target,..}|Self::BuffRemover{target,..}|Self::Damage{target,..}|Self::SavingThrow{target,..}
+                |Self::Spell{target,..}=>Some(target),
+            _=>None
+        }
+    }
+    /// Whether the property is hidden from the log when it is applied
+    #[must_use]
+    pub const fn is_silent(&self)->bool{
+        match self{
+            Self::Action{silent,..}|Self::Adjustment{silent,..}|Self::Branch{silent,..}|Self::Buff{silent,..}|Self::BuffRemover{silent,..}
+                |Self::Damage{silent,..}|Self::Roll{silent,..}|Self::SavingThrow{silent,..}|Self::Spell{silent,..}|Self::Trigger{silent,..}=>*Tracked::get(silent),
+            _=>false
+        }
+    }
+    /// Whether the property has switched itself off, ie an unprepared spell or a toggle that is off
+    #[must_use]
+    pub const fn is_deactivated_by_self(&self)->bool{
+        matches!(self,Self::Spell{deactivated_by_self,..}|Self::Toggle{deactivated_by_self,..} if *Tracked::get(deactivated_by_self))
+    }
+}
+string_enum!{
+    /// The type of a property, without any of its fields
+    /// 
+    /// Serializes as the `type` dicecloud uses. Any type we don't know about is kept as `Unknown`, so it is written back out unchanged
+    #[non_exhaustive]
+    PropKind, Unknown{
+        Action=>"action",
+        Adjustment=>"adjustment",
+        Attribute=>"attribute",
+        Branch=>"branch",
+        Buff=>"buff",
+        BuffRemover=>"buffRemover",
+        Class=>"class",
+        ClassLevel=>"classLevel",
+        Constant=>"constant",
+        Container=>"container",
+        Damage=>"damage",
+        DamageMultiplier=>"damageMultiplier",
+        Effect=>"effect",
+        Feature=>"feature",
+        Folder=>"folder",
+        Item=>"item",
+        Note=>"note",
+        PointBuy=>"pointBuy",
+        Proficiency=>"proficiency",
+        PropertySlot=>"propertySlot",
+        Reference=>"reference",
+        Roll=>"roll",
+        SavingThrow=>"savingThrow",
+        Skill=>"skill",
+        SlotFiller=>"slotFiller",
+        SpellList=>"spellList",
+        Spell=>"spell",
+        Toggle=>"toggle",
+        Trigger=>"trigger",
     }
 }
 /// Enum representing different types of variables in the `creatureVariables` field
 #[derive(Serialize,Deserialize,PartialEq,Debug,Clone)]
 #[serde(rename_all="camelCase",tag="type")]
 pub enum VariableType{
-    Attribute{#[serde(rename="baseValue",default,skip_serializing_if="Option::is_none")] base_value: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(default,skip_serializing_if="Option::is_none")] damage: Option<i64>,
-        #[serde(default)] decimal: bool,
-        #[serde(rename="ignoreLowerLimit",default)] ignore_lower_limit: bool,
-        #[serde(rename="ignoreUpperLimit",default)] ignore_upper_limit: bool,
-        #[serde(rename="hideWhenValueZero",default)] hide_when_value_zero: bool,
-        #[serde(rename="hideWhenTotalZero",default)] hide_when_total_zero: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] reset:Option<String>,
-        #[serde(default)] total: PropVal,
-        #[serde(default)] value: PropVal,#[serde(default)] effects: Vec<Effect>,
-        #[serde(default)] hide: bool, #[serde(default)] overridden: bool},
-    Constant{#[serde(default,skip_serializing_if="Option::is_none")] calculation: Option<String>,
+    Attribute{#[serde(rename="baseValue",default,skip_serializing_if="Tracked::is_absent")] base_value: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] damage: Tracked<Option<i64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] modifier: Tracked<Option<i64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_optional_number")] proficiency: Tracked<Option<f64>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] decimal: Tracked<bool>,
+        #[serde(rename="ignoreLowerLimit",default,skip_serializing_if="Tracked::is_absent")] ignore_lower_limit: Tracked<bool>,
+        #[serde(rename="ignoreUpperLimit",default,skip_serializing_if="Tracked::is_absent")] ignore_upper_limit: Tracked<bool>,
+        #[serde(rename="hideWhenValueZero",default,skip_serializing_if="Tracked::is_absent")] hide_when_value_zero: Tracked<bool>,
+        #[serde(rename="hideWhenTotalZero",default,skip_serializing_if="Tracked::is_absent")] hide_when_total_zero: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] reset: Tracked<Option<Reset>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] total: Tracked<PropVal>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] value: Tracked<PropVal>,#[serde(default,skip_serializing_if="Tracked::is_absent")] effects: Tracked<Vec<Effect>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] hide: Tracked<bool>, #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
+    Constant{#[serde(default,skip_serializing_if="Tracked::is_absent")] calculation: Tracked<Option<String>>,
         value: ParseNode,
-        errors: Vec<ParseError>},
-    Class{#[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>,
-        #[serde(default,rename="slotTags")] slot_tags: Vec<String>,
+        errors: Vec<ParseError>,
+        #[serde(flatten)] extra: Extra},
+    Class{#[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>,
+        #[serde(default,rename="slotTags",skip_serializing_if="Tracked::is_absent")] slot_tags: Tracked<Vec<String>>,
         #[serde(rename="extraTags")] extra_tags: Vec<ExtraTag>,
-        #[serde(rename="slotCondition",default,skip_serializing_if="Option::is_none")] slot_condition: Option<Calculation>,
-        #[serde(default)] level: i64,
-        #[serde(rename="missingLevels",default)] missing_levels: Vec<i64>},
+        #[serde(rename="slotCondition",default,skip_serializing_if="Tracked::is_absent")] slot_condition: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] level: Tracked<i64>,
+        #[serde(rename="missingLevels",default,skip_serializing_if="Tracked::is_absent")] missing_levels: Tracked<Vec<i64>>,
+        #[serde(flatten)] extra: Extra},
     Skill{
-        #[serde(default,skip_serializing_if="Option::is_none")] ability: Option<String>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] ability: Tracked<Option<String>>,
         #[serde(rename="skillType")] skill_type: String,
-        #[serde(rename="baseProficiency",default,skip_serializing_if="Option::is_none")] base_proficiency: Option<f64>,
-        #[serde(rename="baseValue",default,skip_serializing_if="Option::is_none")] base_value: Option<Calculation>,
-        #[serde(default,skip_serializing_if="Option::is_none")] description: Option<CalculatedText>, 
+        #[serde(rename="baseProficiency",default,skip_serializing_if="Tracked::is_absent",serialize_with="serialize_optional_number")] base_proficiency: Tracked<Option<f64>>,
+        #[serde(rename="baseValue",default,skip_serializing_if="Tracked::is_absent")] base_value: Tracked<Option<Calculation>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] description: Tracked<Option<CalculatedText>>, 
         value: i64,
-        #[serde(rename="abilityMod",default)] ability_mod: i64,
-        #[serde(default)] advantage: i64,
-        #[serde(default,rename="passiveBonus")] passive_bonus: i64, proficiency: f64,
-        #[serde(default,rename="conditionalBenefits")] conditional_benifits: Vec<String>,
-        #[serde(default)] fail: i64, #[serde(default)] hide:bool, #[serde(default)] overridden: bool,
-        #[serde(default)] effects: Vec<Effect>},
-    Toggle{#[serde(rename="showUI",default)] show_ui: bool,
-        #[serde(default,skip_serializing_if="Option::is_none")] disabled: Option<bool>,
-        #[serde(default,skip_serializing_if="Option::is_none")] enabled: Option<bool>,
-        #[serde(default,skip_serializing_if="Option::is_none")] condition: Option<Calculation>,
-        #[serde(rename="deactivatedBySelf",default)] deactivated_by_self: bool},
+        #[serde(rename="abilityMod",default,skip_serializing_if="Tracked::is_absent")] ability_mod: Tracked<i64>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] advantage: Tracked<i64>,
+        #[serde(default,rename="passiveBonus",skip_serializing_if="Tracked::is_absent")] passive_bonus: Tracked<i64>, #[serde(serialize_with="serialize_number")] proficiency: f64,
+        #[serde(default,rename="conditionalBenefits",skip_serializing_if="Tracked::is_absent")] conditional_benifits: Tracked<Vec<String>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] fail: Tracked<i64>, #[serde(default,skip_serializing_if="Tracked::is_absent")] hide:Tracked<bool>, #[serde(default,skip_serializing_if="Tracked::is_absent")] overridden: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] effects: Tracked<Vec<Effect>>,
+        #[serde(flatten)] extra: Extra},
+    Toggle{#[serde(rename="showUI",default,skip_serializing_if="Tracked::is_absent")] show_ui: Tracked<bool>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] disabled: Tracked<Option<bool>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] enabled: Tracked<Option<bool>>,
+        #[serde(default,skip_serializing_if="Tracked::is_absent")] condition: Tracked<Option<Calculation>>,
+        #[serde(rename="deactivatedBySelf",default,skip_serializing_if="Tracked::is_absent")] deactivated_by_self: Tracked<bool>,
+        #[serde(flatten)] extra: Extra},
 }
 impl Eq for VariableType{}
 /// Represents a variable in the `creatureVariables` field
@@ -667,23 +1452,27 @@ impl Eq for VariableType{}
 #[serde(rename_all="camelCase")]
 pub struct GenericVariable{
     #[serde(rename="_id")] id: String,
-    variable_name: String,
-    #[serde(default)] name: String,
+    pub(crate) variable_name: String,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] pub(crate) name: Tracked<String>,
     order: i64,
-    #[serde(flatten)] var_type: VariableType,
-    #[serde(default)] library_tags: Vec<String>,
-    #[serde(default,skip_serializing_if="Option::is_none")] library_node_id: Option<String>,
+    #[serde(flatten)] pub(crate) var_type: VariableType,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] library_tags: Tracked<Vec<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")] library_node_id: Tracked<Option<String>>,
 }
 /// represents a thing in the `creatureVariables` field
+/// 
+/// Variants are tried in order, so anything that isn't a recognised variable or a string ends up as `Empty`
 #[derive(Serialize,Deserialize,PartialEq,Eq,Debug,Clone)]
 #[serde(rename_all="camelCase",untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum CharacterVar{
-    Empty{#[serde(skip_serializing_if="Option::is_none",default)] v: Option<i64>},
+    Var(GenericVariable),
     Str(String),
-    Var(GenericVariable)
+    Empty{#[serde(skip_serializing_if="Tracked::is_absent",default)] v: Tracked<Option<i64>>,
+        #[serde(flatten)] extra: Extra}
 }
 impl Default for CharacterVar{
     fn default() -> Self {
-        Self::Empty{v: None}
+        Self::Empty{v: Tracked::default(), extra: Extra::default()}
     }
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+//! Looking up the properties of a character without scanning through all of them
+use std::collections::HashMap;
+
+use crate::data_models::flat_model::{FlatCharacter, FlatProp};
+
+/// Lookups into the properties of a [`FlatCharacter`], built once with [`FlatCharacter::index`]
+///
+/// Everything returned borrows from the character, so it can't be changed while the index is alive.
+/// Where more than one property matches they come back in the order they are in `creature_properties`
+#[derive(Debug,Default,Clone)]
+pub struct PropIndex<'a>{
+    ids: HashMap<&'a str,&'a FlatProp>,
+    variable_names: HashMap<&'a str,Vec<&'a FlatProp>>,
+    tags: HashMap<&'a str,Vec<&'a FlatProp>>,
+    types: HashMap<&'a str,Vec<&'a FlatProp>>,
+}
+impl<'a> PropIndex<'a>{
+    /// Index every property of `character`
+    #[must_use]
+    pub fn new(character: &'a FlatCharacter)->Self{
+        let mut index = Self::default();
+        for prop in &character.creature_properties{
+            index.ids.entry(&prop.id).or_insert(prop);
+            if let Some(name) = prop.prop_type.variable_name().filter(|name|!name.is_empty()){
+                index.variable_names.entry(name).or_default().push(prop);
+            }
+            let mut tags: Vec<&str> = prop.tags.iter().chain(prop.library_tags.iter()).map(String::as_str).collect();
+            tags.sort_unstable();
+            tags.dedup();
+            for tag in tags{
+                index.tags.entry(tag).or_default().push(prop);
+            }
+            index.types.entry(prop.prop_type.type_name()).or_default().push(prop);
+        }
+        index
+    }
+    /// The property with this id
+    ///
+    /// If several share the id, the first is returned
+    #[must_use]
+    pub fn by_id(&self, id: &str)->Option<&'a FlatProp>{
+        self.ids.get(id).copied()
+    }
+    /// The properties with this variable name, such as attributes, skills, constants, toggles, rolls and classes
+    #[must_use]
+    pub fn by_variable_name(&self, name: &str)->&[&'a FlatProp]{
+        self.variable_names.get(name).map_or(&[],Vec::as_slice)
+    }
+    /// The properties with this tag, either in `tags` or `library_tags`
+    #[must_use]
+    pub fn by_tag(&self, tag: &str)->&[&'a FlatProp]{
+        self.tags.get(tag).map_or(&[],Vec::as_slice)
+    }
+    /// The properties of this type, named the way dicecloud does, eg `"attribute"` or `"spellList"`
+    #[must_use]
+    pub fn by_type(&self, type_name: &str)->&[&'a FlatProp]{
+        self.types.get(type_name).map_or(&[],Vec::as_slice)
+    }
+    /// The number of properties indexed by id
+    #[must_use]
+    pub fn len(&self)->usize{
+        self.ids.len()
+    }
+    /// Whether there are no properties indexed
+    #[must_use]
+    pub fn is_empty(&self)->bool{
+        self.ids.is_empty()
+    }
+}
+impl FlatCharacter{
+    /// Build lookups by id, variable name, tag and type over the properties
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::FlatCharacter;
+    /// let character = FlatCharacter::default();
+    /// let index = character.index();
+    /// assert!(index.by_variable_name("strength").is_empty());
+    /// ```
+    #[must_use]
+    pub fn index(&self)->PropIndex<'_>{
+        PropIndex::new(self)
+    }
+}
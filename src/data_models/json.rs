@@ -0,0 +1,124 @@
+//! Reading and writing the models as json, in every form `serde_json` offers
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::data_models::flat_model::{FlatCharacter, FlatProp};
+use crate::data_models::tree_model::{TreeCharacter, TreeProp};
+use crate::error::Error;
+
+fn to_json<T: Serialize>(value: &T, pretty: bool)->Result<String,Error>{
+    Ok(if pretty {serde_json::to_string_pretty(value)?} else {serde_json::to_string(value)?})
+}
+fn to_bytes<T: Serialize>(value: &T, pretty: bool)->Result<Vec<u8>,Error>{
+    Ok(if pretty {serde_json::to_vec_pretty(value)?} else {serde_json::to_vec(value)?})
+}
+fn to_writer<T: Serialize, W: Write>(value: &T, writer: W, pretty: bool)->Result<(),Error>{
+    if pretty {serde_json::to_writer_pretty(writer,value)?} else {serde_json::to_writer(writer,value)?}
+    Ok(())
+}
+fn from_bytes<T: DeserializeOwned>(bytes: &[u8])->Result<T,Error>{
+    Ok(serde_json::from_slice(bytes)?)
+}
+fn from_reader<T: DeserializeOwned, R: Read>(reader: R)->Result<T,Error>{
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Add the json writing methods to a model
+macro_rules! json_output {
+    ($model:ty) => {
+        impl $model{
+            /// Write this as compact json
+            /// # Errors
+            /// If serialization fails, which can only happen if a map has non-string keys
+            pub fn to_json(&self)->Result<String,Error>{
+                to_json(self,false)
+            }
+            /// Write this as indented json
+            /// # Errors
+            /// If serialization fails, which can only happen if a map has non-string keys
+            pub fn to_json_pretty(&self)->Result<String,Error>{
+                to_json(self,true)
+            }
+            /// Write this as compact json bytes
+            /// # Errors
+            /// If serialization fails, which can only happen if a map has non-string keys
+            pub fn to_json_bytes(&self)->Result<Vec<u8>,Error>{
+                to_bytes(self,false)
+            }
+            /// Write this as indented json bytes
+            /// # Errors
+            /// If serialization fails, which can only happen if a map has non-string keys
+            pub fn to_json_bytes_pretty(&self)->Result<Vec<u8>,Error>{
+                to_bytes(self,true)
+            }
+            /// Write this as compact json to a writer
+            /// # Errors
+            /// If writing fails, or serialization fails
+            pub fn to_json_writer<W: Write>(&self, writer: W)->Result<(),Error>{
+                to_writer(self,writer,false)
+            }
+            /// Write this as indented json to a writer
+            /// # Errors
+            /// If writing fails, or serialization fails
+            pub fn to_json_writer_pretty<W: Write>(&self, writer: W)->Result<(),Error>{
+                to_writer(self,writer,true)
+            }
+        }
+    };
+}
+/// Add the json reading methods to a model which has no special error reporting
+macro_rules! json_input {
+    ($model:ty) => {
+        impl $model{
+            /// Read this from json text
+            /// # Errors
+            /// If the json is not valid or doesn't fit the expected format
+            pub fn from_json(json: &str)->Result<Self,Error>{
+                Ok(serde_json::from_str(json)?)
+            }
+            /// Read this from json bytes
+            /// # Errors
+            /// If the json is not valid or doesn't fit the expected format
+            pub fn from_json_bytes(bytes: &[u8])->Result<Self,Error>{
+                from_bytes(bytes)
+            }
+            /// Read this from a reader producing json
+            /// # Errors
+            /// If reading fails, or the json is not valid or doesn't fit the expected format
+            pub fn from_json_reader<R: Read>(reader: R)->Result<Self,Error>{
+                from_reader(reader)
+            }
+        }
+    };
+}
+json_output!(FlatCharacter);
+json_output!(TreeCharacter);
+json_output!(FlatProp);
+json_output!(TreeProp);
+json_input!(TreeCharacter);
+json_input!(FlatProp);
+json_input!(TreeProp);
+
+impl FlatCharacter{
+    /// Convert json bytes from dcv2 into a flat character
+    ///
+    /// Errors are located the same way as [`FlatCharacter::from_json`]
+    /// # Errors
+    /// If the json is not valid or doesn't fit the expected format
+    pub fn from_json_bytes(bytes: &[u8])->Result<Self,Error>{
+        // on invalid utf8 let serde_json say where the bad byte is
+        std::str::from_utf8(bytes).map_or_else(|_|from_bytes(bytes),Self::from_json)
+    }
+    /// Read json from dcv2 into a flat character
+    ///
+    /// The whole input is read before parsing, so errors can be located the same way as [`FlatCharacter::from_json`]
+    /// # Errors
+    /// If reading fails, or the json is not valid or doesn't fit the expected format
+    pub fn from_json_reader<R: Read>(mut reader: R)->Result<Self,Error>{
+        let mut json = String::new();
+        reader.read_to_string(&mut json).map_err(serde_json::Error::io)?;
+        Self::from_json(&json)
+    }
+}
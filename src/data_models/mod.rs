@@ -1,4 +1,17 @@
 #![warn(clippy::pedantic,clippy::nursery,clippy::unwrap_used,clippy::perf)]
 pub mod flat_model;
 pub mod tree_model;
-pub mod generic_model;
\ No newline at end of file
+pub mod generic_model;
+pub mod traversal;
+pub mod validation;
+pub mod index;
+pub mod active;
+pub mod damage;
+pub mod spell;
+pub mod trigger;
+#[cfg(feature="serde_json")]
+pub mod stream;
+#[cfg(feature="serde_json")]
+pub mod borrowed_model;
+#[cfg(feature="serde_json")]
+mod json;
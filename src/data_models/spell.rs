@@ -0,0 +1,211 @@
+//! Reading the free text fields of spells into values that can be filtered on
+//!
+//! Dicecloud stores casting time, range and duration as whatever the user typed, so each parse keeps the text as `Raw` when it isn't recognised.
+//! The spell itself always keeps the original text, so nothing is lost by parsing
+use std::fmt;
+
+use crate::data_models::generic_model::{Components, PropType, SpellSchool};
+
+/// Split a leading whole number off `text`, eg `"60 ft."` into `(60, "ft.")`
+fn leading_number(text: &str)->Option<(u32,&str)>{
+    let end = text.find(|c: char|!c.is_ascii_digit()).unwrap_or(text.len());
+    let number = text[..end].parse().ok()?;
+    Some((number,text[end..].trim_start_matches([' ','-'])))
+}
+/// Whether `unit` is `singular` or its plural
+fn is_unit(unit: &str, singular: &str)->bool{
+    unit.strip_prefix(singular).is_some_and(|rest|rest.is_empty() || rest=="s")
+}
+/// How long a spell takes to cast
+#[derive(PartialEq,Eq,Debug,Clone,Hash)]
+pub enum CastingTime{
+    Action,
+    BonusAction,
+    /// A reaction, whatever triggers it
+    Reaction,
+    Minutes(u32),
+    Hours(u32),
+    /// Text that couldn't be read
+    Raw(String)
+}
+impl CastingTime{
+    /// Read a casting time such as `1 action`, `1 reaction, which you take when...` or `10 minutes`
+    #[must_use]
+    pub fn parse(text: &str)->Self{
+        let lower = text.trim().to_lowercase();
+        // anything after a comma explains when a reaction is taken
+        let head = lower.split(',').next().unwrap_or_default().trim();
+        match leading_number(head){
+            Some((1,"action"))=>Self::Action,
+            Some((1,"bonus action"))=>Self::BonusAction,
+            Some((1,"reaction"))=>Self::Reaction,
+            Some((n,unit)) if is_unit(unit,"minute")=>Self::Minutes(n),
+            Some((n,unit)) if is_unit(unit,"hour")=>Self::Hours(n),
+            _=>Self::Raw(text.to_string())
+        }
+    }
+}
+impl fmt::Display for CastingTime{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Self::Action=>write!(f,"1 action"),
+            Self::BonusAction=>write!(f,"1 bonus action"),
+            Self::Reaction=>write!(f,"1 reaction"),
+            Self::Minutes(1)=>write!(f,"1 minute"),
+            Self::Minutes(n)=>write!(f,"{n} minutes"),
+            Self::Hours(1)=>write!(f,"1 hour"),
+            Self::Hours(n)=>write!(f,"{n} hours"),
+            Self::Raw(text)=>write!(f,"{text}")
+        }
+    }
+}
+/// How far away a spell can reach
+#[derive(PartialEq,Eq,Debug,Clone,Hash)]
+pub enum SpellRange{
+    /// The caster, including spells like `Self (15-foot cone)` which spread out from them
+    Self_,
+    Touch,
+    Sight,
+    Unlimited,
+    Feet(u32),
+    Miles(u32),
+    /// Text that couldn't be read
+    Raw(String)
+}
+impl SpellRange{
+    /// Read a range such as `60 feet`, `60 ft`, `Touch` or `Self (30-foot radius)`
+    #[must_use]
+    pub fn parse(text: &str)->Self{
+        let lower = text.trim().to_lowercase();
+        match lower.as_str(){
+            "touch"=>return Self::Touch,
+            "sight"=>return Self::Sight,
+            "unlimited"=>return Self::Unlimited,
+            _=>{}
+        }
+        if lower=="self" || lower.starts_with("self (") || lower.starts_with("self("){
+            return Self::Self_;
+        }
+        match leading_number(&lower){
+            Some((n,"ft" | "ft." | "foot" | "feet"))=>Self::Feet(n),
+            Some((n,unit)) if is_unit(unit,"mile")=>Self::Miles(n),
+            _=>Self::Raw(text.to_string())
+        }
+    }
+}
+impl fmt::Display for SpellRange{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Self::Self_=>write!(f,"Self"),
+            Self::Touch=>write!(f,"Touch"),
+            Self::Sight=>write!(f,"Sight"),
+            Self::Unlimited=>write!(f,"Unlimited"),
+            Self::Feet(n)=>write!(f,"{n} feet"),
+            Self::Miles(1)=>write!(f,"1 mile"),
+            Self::Miles(n)=>write!(f,"{n} miles"),
+            Self::Raw(text)=>write!(f,"{text}")
+        }
+    }
+}
+/// How long a spell lasts
+///
+/// Whether it needs concentration is the spell's `concentration` field, a leading `Concentration, up to` is skipped
+#[derive(PartialEq,Eq,Debug,Clone,Hash)]
+pub enum SpellDuration{
+    Instantaneous,
+    Rounds(u32),
+    Minutes(u32),
+    Hours(u32),
+    Days(u32),
+    UntilDispelled,
+    Special,
+    /// Text that couldn't be read
+    Raw(String)
+}
+impl SpellDuration{
+    /// Read a duration such as `Instantaneous`, `1 round`, `Concentration, up to 10 minutes` or `Until dispelled`
+    #[must_use]
+    pub fn parse(text: &str)->Self{
+        let lower = text.trim().to_lowercase();
+        let rest = lower.strip_prefix("concentration").map_or(lower.as_str(),|rest|rest.trim_start_matches([',',' ']));
+        let rest = rest.strip_prefix("up to").map_or(rest,str::trim_start);
+        match rest{
+            "instantaneous"=>return Self::Instantaneous,
+            "special"=>return Self::Special,
+            _ if rest.starts_with("until dispelled")=>return Self::UntilDispelled,
+            _=>{}
+        }
+        match leading_number(rest){
+            Some((n,unit)) if is_unit(unit,"round")=>Self::Rounds(n),
+            Some((n,unit)) if is_unit(unit,"minute")=>Self::Minutes(n),
+            Some((n,unit)) if is_unit(unit,"hour")=>Self::Hours(n),
+            Some((n,unit)) if is_unit(unit,"day")=>Self::Days(n),
+            _=>Self::Raw(text.to_string())
+        }
+    }
+}
+impl fmt::Display for SpellDuration{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (n,unit) = match self{
+            Self::Instantaneous=>return write!(f,"Instantaneous"),
+            Self::UntilDispelled=>return write!(f,"Until dispelled"),
+            Self::Special=>return write!(f,"Special"),
+            Self::Raw(text)=>return write!(f,"{text}"),
+            Self::Rounds(n)=>(n,"round"),
+            Self::Minutes(n)=>(n,"minute"),
+            Self::Hours(n)=>(n,"hour"),
+            Self::Days(n)=>(n,"day"),
+        };
+        write!(f,"{n} {unit}{}",if *n==1 {""} else {"s"})
+    }
+}
+impl PropType{
+    /// The level of a spell, 0 for cantrips. `None` for anything else
+    #[must_use]
+    pub const fn spell_level(&self)->Option<i64>{
+        match self{
+            Self::Spell{level,..}=>Some(*level),
+            _=>None
+        }
+    }
+    /// The school of a spell
+    #[must_use]
+    pub const fn spell_school(&self)->Option<&SpellSchool>{
+        match self{
+            Self::Spell{school,..}=>Some(school),
+            _=>None
+        }
+    }
+    /// The components needed to cast a spell
+    #[must_use]
+    pub const fn spell_components(&self)->Option<&Components>{
+        match self{
+            Self::Spell{components,..}=>Some(components),
+            _=>None
+        }
+    }
+    /// The casting time of a spell, if it has one
+    #[must_use]
+    pub fn spell_casting_time(&self)->Option<CastingTime>{
+        match self{
+            Self::Spell{casting_time,..}=>casting_time.as_deref().map(CastingTime::parse),
+            _=>None
+        }
+    }
+    /// The range of a spell, if it has one
+    #[must_use]
+    pub fn spell_range(&self)->Option<SpellRange>{
+        match self{
+            Self::Spell{range,..}=>range.as_deref().map(SpellRange::parse),
+            _=>None
+        }
+    }
+    /// The duration of a spell, if it has one
+    #[must_use]
+    pub fn spell_duration(&self)->Option<SpellDuration>{
+        match self{
+            Self::Spell{duration,..}=>duration.as_deref().map(SpellDuration::parse),
+            _=>None
+        }
+    }
+}
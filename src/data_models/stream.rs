@@ -0,0 +1,277 @@
+//! Reading a character one piece at a time, for exports too large to comfortably hold in memory
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::data_models::flat_model::{self, FlatCharacter, FlatProp};
+use crate::data_models::generic_model::{CharacterVar, CreatureInfo};
+use crate::error::Error;
+
+/// One top level piece of a character export, as yielded by [`CharacterReader`]
+#[derive(PartialEq,Debug,Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum CharacterPart{
+    /// An entry of `creatures`
+    Creature(CreatureInfo),
+    /// An entry of `creatureProperties`
+    Property(FlatProp),
+    /// An entry of `creatureVariables`
+    Variables(HashMap<String,CharacterVar>),
+    /// Any other top level field, with its key
+    Other(String,Value),
+}
+#[derive(PartialEq,Eq,Debug,Clone,Copy)]
+enum State{
+    Start,
+    /// Between top level fields, `first` if none have been read yet
+    Fields{first: bool},
+    /// Inside one of the arrays, having read `index` entries
+    Array{part: Part, index: usize},
+    Done,
+}
+#[derive(PartialEq,Eq,Debug,Clone,Copy)]
+enum Part{
+    Creatures,
+    Properties,
+    Variables,
+}
+impl Part{
+    fn from_key(key: &str)->Option<Self>{
+        match key{
+            "creatures"=>Some(Self::Creatures),
+            "creatureProperties"=>Some(Self::Properties),
+            "creatureVariables"=>Some(Self::Variables),
+            _=>None
+        }
+    }
+    const fn key(self)->&'static str{
+        match self{
+            Self::Creatures=>"creatures",
+            Self::Properties=>"creatureProperties",
+            Self::Variables=>"creatureVariables"
+        }
+    }
+}
+/// Reads a character export incrementally, yielding each creature, property and variable map as soon as it has been read
+///
+/// Only one entry is held in memory at a time. If a single entry doesn't fit the model its error is yielded and reading carries on,
+/// but invalid json stops the reader
+///
+/// # Examples
+/// ```
+/// use dicecloud_models::{CharacterPart, CharacterReader};
+/// let json = r#"{"creatures": [], "creatureProperties": [], "creatureVariables": [{}], "exportedAt": "today"}"#;
+/// let parts: Vec<CharacterPart> = CharacterReader::new(json.as_bytes()).collect::<Result<_,_>>().unwrap();
+/// assert_eq!(parts.len(), 2);
+/// assert!(matches!(&parts[1], CharacterPart::Other(key, _) if key == "exportedAt"));
+/// ```
+pub struct CharacterReader<R>{
+    reader: BufReader<R>,
+    state: State,
+    /// How many bytes have been consumed, for error messages
+    offset: usize,
+}
+impl<R: Read> CharacterReader<R>{
+    /// Start reading a character from `reader`
+    pub fn new(reader: R)->Self{
+        Self{reader: BufReader::new(reader), state: State::Start, offset: 0}
+    }
+    /// Only the properties of the character, skipping everything else
+    ///
+    /// Usefull for filtering properties without keeping any of the rest of the character
+    pub fn properties(self)->impl Iterator<Item=Result<FlatProp,Error>>{
+        self.filter_map(|part|match part{
+            Ok(CharacterPart::Property(prop))=>Some(Ok(prop)),
+            Ok(_)=>None,
+            Err(e)=>Some(Err(e))
+        })
+    }
+    fn peek(&mut self)->Result<Option<u8>,Error>{
+        let buf = self.reader.fill_buf().map_err(serde_json::Error::io)?;
+        Ok(buf.first().copied())
+    }
+    fn bump(&mut self){
+        self.reader.consume(1);
+        self.offset+=1;
+    }
+    fn next_byte(&mut self)->Result<Option<u8>,Error>{
+        let byte = self.peek()?;
+        if byte.is_some(){
+            self.bump();
+        }
+        Ok(byte)
+    }
+    /// Skip whitespace and return the next byte without consuming it
+    fn peek_token(&mut self)->Result<Option<u8>,Error>{
+        while let Some(b) = self.peek()?{
+            if !b.is_ascii_whitespace(){
+                return Ok(Some(b));
+            }
+            self.bump();
+        }
+        Ok(None)
+    }
+    fn unexpected(&self, path: &str, expected: &str)->Error{
+        Error::InvalidField{id: None, path: path.to_string(), message: format!("expected {expected} at byte {}",self.offset)}
+    }
+    fn expect(&mut self, byte: u8, path: &str)->Result<(),Error>{
+        if self.peek_token()?==Some(byte){
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected(path,&format!("`{}`",char::from(byte))))
+        }
+    }
+    /// Read the rest of a string whose opening quote has been consumed, including the closing quote
+    fn read_string(&mut self, raw: &mut Vec<u8>, path: &str)->Result<(),Error>{
+        loop{
+            let Some(b) = self.next_byte()? else {
+                return Err(self.unexpected(path,"the end of a string"));
+            };
+            raw.push(b);
+            match b{
+                b'"'=>return Ok(()),
+                b'\\'=>{
+                    if let Some(escaped) = self.next_byte()?{
+                        raw.push(escaped);
+                    }
+                },
+                _=>{}
+            }
+        }
+    }
+    /// Read the raw bytes of the next json value, leaving checking it to `serde_json`
+    fn read_value(&mut self, path: &str)->Result<Vec<u8>,Error>{
+        let mut raw = Vec::new();
+        let mut depth = 0usize;
+        let Some(first) = self.peek_token()? else {
+            return Err(self.unexpected(path,"a value"));
+        };
+        if !matches!(first,b'"'|b'{'|b'['){
+            // a number, boolean or null runs until the next delimiter
+            while let Some(b) = self.peek()?{
+                if b.is_ascii_whitespace()||matches!(b,b','|b'}'|b']'){
+                    break;
+                }
+                raw.push(b);
+                self.bump();
+            }
+            return if raw.is_empty() {Err(self.unexpected(path,"a value"))} else {Ok(raw)};
+        }
+        loop{
+            let Some(b) = self.next_byte()? else {
+                return Err(self.unexpected(path,"the end of the value"));
+            };
+            raw.push(b);
+            match b{
+                b'"'=>self.read_string(&mut raw,path)?,
+                b'{'|b'['=>depth+=1,
+                b'}'|b']'=>depth = depth.saturating_sub(1),
+                _=>{}
+            }
+            if depth==0{
+                return Ok(raw);
+            }
+        }
+    }
+    fn read_key(&mut self)->Result<String,Error>{
+        if self.peek_token()?!=Some(b'"'){
+            return Err(self.unexpected("","a field name"));
+        }
+        let raw = self.read_value("")?;
+        Ok(serde_json::from_slice(&raw)?)
+    }
+    /// Move to the next entry and read it
+    ///
+    /// The outer error is for json too broken to carry on reading, the inner one for a single entry that doesn't fit the model
+    fn advance(&mut self)->Result<Option<Result<CharacterPart,Error>>,Error>{
+        loop{
+            match self.state{
+                State::Done=>return Ok(None),
+                State::Start=>{
+                    self.expect(b'{',"")?;
+                    self.state = State::Fields{first: true};
+                },
+                State::Fields{first}=>{
+                    if self.peek_token()?==Some(b'}'){
+                        self.bump();
+                        self.state = State::Done;
+                        return Ok(None);
+                    }
+                    if !first{
+                        self.expect(b',',"")?;
+                    }
+                    self.state = State::Fields{first: false};
+                    let key = self.read_key()?;
+                    self.expect(b':',&key)?;
+                    if let Some(part) = Part::from_key(&key){
+                        self.expect(b'[',&key)?;
+                        self.state = State::Array{part, index: 0};
+                    } else {
+                        let raw = self.read_value(&key)?;
+                        let value = serde_json::from_slice(&raw).map_err(Error::from);
+                        return Ok(Some(value.map(|value|CharacterPart::Other(key,value))));
+                    }
+                },
+                State::Array{part,index}=>{
+                    if self.peek_token()?==Some(b']'){
+                        self.bump();
+                        self.state = State::Fields{first: false};
+                        continue;
+                    }
+                    let path = format!("{}[{index}]",part.key());
+                    if index>0{
+                        self.expect(b',',&path)?;
+                    }
+                    self.state = State::Array{part, index: index+1};
+                    let raw = self.read_value(&path)?;
+                    return Ok(Some(match part{
+                        Part::Creatures=>read_entry(&raw,&path).map(CharacterPart::Creature),
+                        Part::Properties=>read_entry(&raw,&path).map(CharacterPart::Property),
+                        Part::Variables=>read_entry(&raw,&path).map(CharacterPart::Variables)
+                    }));
+                }
+            }
+        }
+    }
+}
+/// Deserialize one array entry, saying which entry and field it was if it doesn't fit
+fn read_entry<T: DeserializeOwned>(raw: &[u8], path: &str)->Result<T,Error>{
+    serde_json::from_slice(raw).or_else(|e|{
+        if !e.is_data(){
+            return Err(Error::Json(e));
+        }
+        // only pay for working out the field once something is wrong with it
+        let item: Value = serde_json::from_slice(raw)?;
+        flat_model::read_entry(&item,path)
+    })
+}
+impl<R: Read> Iterator for CharacterReader<R>{
+    type Item = Result<CharacterPart,Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().unwrap_or_else(|e|{
+            // after broken json there is no telling where the next entry starts
+            self.state = State::Done;
+            Some(Err(e))
+        })
+    }
+}
+impl FromIterator<CharacterPart> for FlatCharacter{
+    /// Put a character back together from its parts, for example after filtering a [`CharacterReader`]
+    fn from_iter<T: IntoIterator<Item = CharacterPart>>(iter: T) -> Self {
+        let mut character = Self::default();
+        for part in iter{
+            match part{
+                CharacterPart::Creature(creature)=>character.creatures.push(creature),
+                CharacterPart::Property(prop)=>character.creature_properties.push(prop),
+                CharacterPart::Variables(vars)=>character.creature_variables.push(vars),
+                CharacterPart::Other(key,value)=>{
+                    character.extra.as_map_mut().insert(key,value);
+                }
+            }
+        }
+        character
+    }
+}
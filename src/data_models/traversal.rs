@@ -0,0 +1,168 @@
+//! Walking every property of a tree, rather than one level at a time
+//!
+//! Each iterator yields a [`Visit`], giving the property along with how deep it is and the properties above it.
+//! Children are always visited in `order`
+use std::collections::VecDeque;
+use std::slice;
+
+use crate::data_models::tree_model::{Children, TreeCharacter, TreeProp};
+
+/// A property reached while walking the tree
+#[derive(PartialEq,Debug,Clone)]
+pub struct Visit<'a>{
+    pub prop: &'a TreeProp,
+    /// How many properties are above this one, 0 for the starting level
+    pub depth: usize,
+    /// The properties above this one, outermost first
+    pub ancestors: Vec<&'a TreeProp>,
+}
+impl<'a> Visit<'a>{
+    /// The property directly above this one, if there is one
+    #[must_use]
+    pub fn parent(&self)->Option<&'a TreeProp>{
+        self.ancestors.last().copied()
+    }
+}
+/// Visits each property before its children
+#[derive(Debug,Clone)]
+pub struct PreOrder<'a>{
+    /// An iterator over each level being walked, one more than `path`
+    stack: Vec<Children<'a>>,
+    path: Vec<&'a TreeProp>,
+}
+impl<'a> Iterator for PreOrder<'a>{
+    type Item = Visit<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(level) = self.stack.last_mut(){
+            if let Some(prop) = level.next(){
+                let visit = Visit{prop, depth: self.path.len(), ancestors: self.path.clone()};
+                self.path.push(prop);
+                self.stack.push(prop.children());
+                return Some(visit);
+            }
+            self.stack.pop();
+            self.path.pop();
+        }
+        None
+    }
+}
+/// Visits each property after its children
+#[derive(Debug,Clone)]
+pub struct PostOrder<'a>{
+    stack: Vec<Children<'a>>,
+    path: Vec<&'a TreeProp>,
+}
+impl<'a> Iterator for PostOrder<'a>{
+    type Item = Visit<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop{
+            let level = self.stack.last_mut()?;
+            if let Some(prop) = level.next(){
+                self.path.push(prop);
+                self.stack.push(prop.children());
+                continue;
+            }
+            // every child of the innermost property has been visited, so it is next
+            self.stack.pop();
+            let prop = self.path.pop()?;
+            return Some(Visit{prop, depth: self.path.len(), ancestors: self.path.clone()});
+        }
+    }
+}
+/// Visits each level of the tree in turn
+#[derive(Debug,Clone)]
+pub struct BreadthFirst<'a>{
+    queue: VecDeque<Visit<'a>>,
+}
+impl<'a> Iterator for BreadthFirst<'a>{
+    type Item = Visit<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.queue.pop_front()?;
+        let mut ancestors = visit.ancestors.clone();
+        ancestors.push(visit.prop);
+        self.queue.extend(visit.prop.children().map(|prop|Visit{prop, depth: visit.depth+1, ancestors: ancestors.clone()}));
+        Some(visit)
+    }
+}
+fn pre_order(start: Children<'_>)->PreOrder<'_>{
+    PreOrder{stack: vec![start], path: Vec::new()}
+}
+fn post_order(start: Children<'_>)->PostOrder<'_>{
+    PostOrder{stack: vec![start], path: Vec::new()}
+}
+fn breadth_first(start: Children<'_>)->BreadthFirst<'_>{
+    BreadthFirst{queue: start.map(|prop|Visit{prop, depth: 0, ancestors: Vec::new()}).collect()}
+}
+/// Call `f` on `prop` then everything under it, passing the depth and the ids of the properties above
+fn visit_mut<F: FnMut(&mut TreeProp,usize,&[String])>(prop: &mut TreeProp, f: &mut F, path: &mut Vec<String>){
+    f(prop,path.len(),path);
+    path.push(prop.id.clone());
+    for child in prop.children_mut(){
+        visit_mut(child,f,path);
+    }
+    path.pop();
+}
+impl TreeProp{
+    /// Walk this property and everything under it, each property before its children
+    ///
+    /// This property is visited first, at depth 0
+    #[must_use]
+    pub fn pre_order(&self)->PreOrder<'_>{
+        pre_order(slice::from_ref(self).iter())
+    }
+    /// Walk this property and everything under it, each property after its children
+    ///
+    /// This property is visited last, at depth 0
+    #[must_use]
+    pub fn post_order(&self)->PostOrder<'_>{
+        post_order(slice::from_ref(self).iter())
+    }
+    /// Walk this property and everything under it a level at a time
+    #[must_use]
+    pub fn breadth_first(&self)->BreadthFirst<'_>{
+        breadth_first(slice::from_ref(self).iter())
+    }
+    /// Call `f` on this property and everything under it, each property before its children
+    ///
+    /// `f` is given the property, its depth, and the ids of the properties above it within this subtree.
+    /// Any children it adds or removes are reflected in the rest of the walk
+    pub fn visit_mut<F: FnMut(&mut Self,usize,&[String])>(&mut self, mut f: F){
+        visit_mut(self,&mut f,&mut Vec::new());
+    }
+}
+impl TreeCharacter{
+    /// Walk every property, each property before its children
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::{FlatCharacter, TreeCharacter};
+    /// let tree = TreeCharacter::build_tree(FlatCharacter::default());
+    /// for visit in tree.pre_order(){
+    ///     println!("{}{}", "  ".repeat(visit.depth), visit.prop.id);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn pre_order(&self)->PreOrder<'_>{
+        pre_order(self.roots())
+    }
+    /// Walk every property, each property after its children
+    #[must_use]
+    pub fn post_order(&self)->PostOrder<'_>{
+        post_order(self.roots())
+    }
+    /// Walk every property a level at a time, starting with the roots
+    #[must_use]
+    pub fn breadth_first(&self)->BreadthFirst<'_>{
+        breadth_first(self.roots())
+    }
+    /// Call `f` on every property, each property before its children
+    ///
+    /// `f` is given the property, its depth, and the ids of the properties above it.
+    /// The index is rebuilt afterwards, so `f` is free to add or remove children
+    pub fn visit_mut<F: FnMut(&mut TreeProp,usize,&[String])>(&mut self, mut f: F){
+        for root in self.roots_mut(){
+            visit_mut(root,&mut f,&mut Vec::new());
+        }
+        self.reindex();
+    }
+}
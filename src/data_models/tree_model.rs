@@ -1,51 +1,62 @@
-use std::collections::HashMap;
-use std::collections::hash_map::{Values, ValuesMut};
+use core::fmt;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::slice::{Iter, IterMut};
 
-use crate::data_models::generic_model::{CharacterVar, CreatureInfo, Icon, Identifier, PropType};
+use crate::data_models::generic_model::{serialize_prop_type, CharacterVar, CreatureInfo, Extra, Icon, Identifier, PropType, Tracked};
 use crate::data_models::flat_model::{FlatCharacter,FlatProp};
+use crate::error::Error;
 use serde::{Serialize,Deserialize};
-/// an iterator over references to the children of a node
-pub type Children<'a> = Values<'a,String,TreeProp>;
-/// An iterator over mutable references to the children of a node
-pub type ChildrenMut<'a>= ValuesMut<'a,String,TreeProp>;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+/// an iterator over references to the children of a node, in `order`
+pub type Children<'a> = Iter<'a,TreeProp>;
+/// An iterator over mutable references to the children of a node, in `order`
+pub type ChildrenMut<'a>= IterMut<'a,TreeProp>;
 /// Represents a property as it appears on the tree, by building children under parents
+/// 
+/// Children are kept sorted by their `order`, so they come out in the same sequence as on the character sheet.
+/// If you change the `order` of children through [`TreeProp::children_mut`], call [`TreeProp::sort_children`] afterwards
+/// 
+/// Any fields that aren't modelled are kept in the `extra` of the property type
 #[derive(Serialize, Deserialize,PartialEq,Debug,Default,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct TreeProp{
     #[serde(rename="_id")]
     pub id: String,
     #[serde(flatten)]
+    #[serde(serialize_with="serialize_prop_type")]
     pub prop_type: PropType,
     pub tags: Vec<String>,
     pub order: usize,
     pub parent: Identifier,
     pub ancestors: Vec<Identifier>,
-    #[serde(default)]
-    pub library_node_id: Option<String>,
-    #[serde(default)]
-    pub color: Option<String>,
-    #[serde(default)]
-    pub icon: Option<Icon>,
-    #[serde(default)]
-    pub library_tags: Vec<String>,
-    #[serde(default)]
-    pub deactivated_by_toggle: Option<bool>,
-    #[serde(default)]
-    pub deactivated_by_ancestor: Option<bool>,
-    #[serde(default)]
-    pub inactive: bool,
-    #[serde(default)]
-    pub removed: bool,
-    #[serde(default)]
-    pub removed_at: Option<String>,
-    #[serde(default)]
-    pub removed_with: Option<String>,
-    #[serde(default)]
-    pub dirty: Option<bool>,
-    #[serde(default,rename="_migrationError")]
-    pub migration_error: Vec<String>,
-    #[serde(default)]
-    child_map: HashMap<String,TreeProp>
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub library_node_id: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub color: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub icon: Tracked<Option<Icon>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub library_tags: Tracked<Vec<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub deactivated_by_toggle: Tracked<Option<bool>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub deactivated_by_ancestor: Tracked<Option<bool>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub inactive: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed: Tracked<bool>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed_at: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub removed_with: Tracked<Option<String>>,
+    #[serde(default,skip_serializing_if="Tracked::is_absent")]
+    pub dirty: Tracked<Option<bool>>,
+    #[serde(default,rename="_migrationError",skip_serializing_if="Tracked::is_absent")]
+    pub migration_error: Tracked<Vec<String>>,
+    #[serde(default,alias="childMap",deserialize_with="siblings",skip_serializing_if="Vec::is_empty")]
+    children: Vec<Self>
 }
 impl TreeProp{
     /// Build a tree prop from a flat prop with no children
@@ -71,7 +82,7 @@ impl TreeProp{
             removed_with: p.removed_with,
             dirty: p.dirty,
             migration_error: p.migration_error,
-            child_map: HashMap::new()
+            children: Vec::new()
         }
     }
     #[allow(clippy::use_self)]
@@ -95,9 +106,11 @@ impl TreeProp{
             removed_with: self.removed_with,
             dirty: self.dirty,
             migration_error: self.migration_error
-        },self.child_map.into_values().collect())
+        },self.children)
     }
     /// Consumes this property and add this prop and all children to a specified vector 
+    /// 
+    /// Properties are added in pre-order, each parent followed by its children in `order`
     pub fn flatten(self,working: &mut Vec<FlatProp>){
         let flt = self.into_flat_prop();
         working.push(flt.0);
@@ -105,10 +118,10 @@ impl TreeProp{
             prp.flatten(working);
         }
     }
-    /// add a child to this node in the tree
+    /// add a child to this node in the tree, after any children with the same `order`
     #[allow(clippy::use_self)]
     pub fn add_child(&mut self, child: TreeProp){
-        self.child_map.insert(child.id.clone(),child);
+        insert_ordered(&mut self.children,child);
     }
     /// recursively insert a property based on its ancestors
     #[allow(clippy::use_self)]
@@ -118,46 +131,162 @@ impl TreeProp{
         let mut piter = rel_path.into_iter();
         let id = piter.next();
         if let Some(t)=id{
-            self.child_map.entry(t).or_default()
-                .recurse_insert(piter, prop);
+            let pos = if let Some(pos) = self.children.iter().position(|c|c.id==t){
+                pos
+            } else {
+                insert_ordered(&mut self.children,Self{id: t, ..Self::default()})
+            };
+            self.children[pos].recurse_insert(piter, prop);
         } else {
             self.add_child(prop);
         }
     }
-    /// Returns an iterator over references to the children
-    #[must_use]
-    pub fn children(&self)->Children{
-        self.child_map.values()
+    /// Returns an iterator over references to the children, in `order`
+    #[must_use="the children are only walked when the iterator is used"]
+    pub fn children(&self)->Children<'_>{
+        self.children.iter()
     }
-    /// Returns an iterator over mutible references to the children
-    pub fn children_mut(&mut self)->ChildrenMut{
-        self.child_map.values_mut()
+    /// Returns an iterator over mutible references to the children, in `order`
+    pub fn children_mut(&mut self)->ChildrenMut<'_>{
+        self.children.iter_mut()
     }
-    /// Takes the children out from the node, returning them as a vec and leaving nothing behind
+    /// Takes the children out from the node, returning them as a vec in `order` and leaving nothing behind
     pub fn take_children(&mut self)->Vec<Self>{
-        std::mem::take(&mut self.child_map).into_values().collect()
+        std::mem::take(&mut self.children)
+    }
+    /// Re-sort the children of this node and all its descendants by `order`
+    /// 
+    /// Only needed after changing `order` through [`TreeProp::children_mut`]
+    pub fn sort_children(&mut self){
+        self.children.sort_by_key(|c|c.order);
+        self.children.iter_mut().for_each(Self::sort_children);
     }
 }
+/// Insert a property into a list sorted by `order`, after any with the same `order`
+/// 
+/// Returns where it went
+fn insert_ordered(list: &mut Vec<TreeProp>, prop: TreeProp)->usize{
+    let pos = list.partition_point(|p|p.order<=prop.order);
+    list.insert(pos,prop);
+    pos
+}
 impl PartialOrd for TreeProp{
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         self.order.partial_cmp(&other.order)
     }
 }
-/// An iterator over references to the roots of a node
-pub type Roots<'a> = Values<'a,String,TreeProp>;
-/// An iterator over mutable references to the roots of a node
-pub type RootsMut<'a> = ValuesMut<'a, String, TreeProp>;
+/// An iterator over references to the roots of a node, in `order`
+pub type Roots<'a> = Iter<'a,TreeProp>;
+/// An iterator over mutable references to the roots of a node, in `order`
+pub type RootsMut<'a> = IterMut<'a, TreeProp>;
 /// Represents a full character whose properties are in tree form
-#[derive(Serialize, Deserialize,PartialEq,Default,Clone)]
+/// 
+/// Roots are kept sorted by their `order`, like the children of each [`TreeProp`]
+/// 
+/// An index of where each property is is kept alongside the tree, so [`TreeCharacter::get`] and friends don't have to search it.
+/// The index holds the position of each property among its siblings at every level down from the root, and changes made through this type keep it up to date.
+/// Changes made through [`TreeProp`] methods (like adding children) aren't seen until [`TreeCharacter::reindex`] is called
+#[derive(Serialize,Debug,Default,Clone)]
 #[serde(rename_all="camelCase")]
 pub struct TreeCharacter{
     pub creatures: Vec<CreatureInfo>,
-    pub(crate) creature_properties_tmap: HashMap<String,TreeProp>,
-    pub creature_variables: Vec<HashMap<String, CharacterVar>>
+    pub(crate) creature_properties_tree: Vec<TreeProp>,
+    pub creature_variables: Vec<HashMap<String, CharacterVar>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+    /// The position among its siblings of each property, and of each property above it, from the root down
+    #[serde(skip)]
+    index: HashMap<String,Vec<usize>>
+}
+impl PartialEq for TreeCharacter{
+    fn eq(&self, other: &Self) -> bool {
+        // the index is derived from the tree, so isn't compared
+        self.creatures==other.creatures && self.creature_properties_tree==other.creature_properties_tree
+            && self.creature_variables==other.creature_variables && self.extra==other.extra
+    }
+}
+impl<'de> Deserialize<'de> for TreeCharacter{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all="camelCase")]
+        struct Fields{
+            creatures: Vec<CreatureInfo>,
+            #[serde(alias="creaturePropertiesTmap",deserialize_with="siblings")]
+            creature_properties_tree: Vec<TreeProp>,
+            creature_variables: Vec<HashMap<String, CharacterVar>>,
+            #[serde(flatten)]
+            extra: Extra
+        }
+        let f = Fields::deserialize(deserializer)?;
+        Ok(Self::new(f.creatures,f.creature_properties_tree,f.creature_variables,f.extra))
+    }
+}
+/// Read sibling properties, either as a list or as the map from id to property that `childMap` and `creaturePropertiesTmap` used to be
+fn siblings<'de, D: serde::Deserializer<'de>>(deserializer: D)->Result<Vec<TreeProp>,D::Error>{
+    struct SiblingsVisitor;
+    impl<'de> Visitor<'de> for SiblingsVisitor{
+        type Value = Vec<TreeProp>;
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f,"a list of properties, or a map from id to property")
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut props = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+            while let Some(prop) = seq.next_element()?{
+                props.push(prop);
+            }
+            Ok(props)
+        }
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut props = Vec::with_capacity(map.size_hint().unwrap_or_default());
+            while let Some((_,prop)) = map.next_entry::<de::IgnoredAny,TreeProp>()?{
+                props.push(prop);
+            }
+            // the map kept no order of its own
+            props.sort_by_key(|prop|prop.order);
+            Ok(props)
+        }
+    }
+    deserializer.deserialize_any(SiblingsVisitor)
+}
+/// What [`TreeCharacter::build_tree_with`] does with properties whose ancestors aren't part of the character
+#[derive(PartialEq,Eq,Debug,Default,Clone,Copy)]
+pub enum OrphanHandling{
+    /// Stand in an empty folder for each missing ancestor, so orphans stay at the depth their ancestors say
+    #[default]
+    Placeholders,
+    /// Attach each orphan, along with everything under it, to a single new folder at the root
+    ///
+    /// The folder gets a fresh id in dicecloud's style, given in [`TreeReport::orphan_root`],
+    /// and the orphans are relinked so their `parent` and `ancestors` point at it
+    SyntheticRoot,
+}
+/// Everything [`TreeCharacter::build_tree_with`] had to work around
+#[derive(Debug,Default)]
+pub struct TreeReport{
+    /// An [`Error::BrokenLinkage`] for each property whose parent isn't part of the character
+    pub orphans: Vec<Error>,
+    /// The ids of the placeholder folders standing in for missing ancestors
+    pub placeholders: Vec<String>,
+    /// The id of the folder orphans were attached to with [`OrphanHandling::SyntheticRoot`], if any were
+    pub orphan_root: Option<String>,
+}
+impl TreeReport{
+    /// Whether every property was placed under its real ancestors
+    #[must_use]
+    pub const fn is_clean(&self)->bool{
+        self.orphans.is_empty()
+    }
 }
 impl TreeCharacter{
+    fn new(creatures: Vec<CreatureInfo>, creature_properties_tree: Vec<TreeProp>,
+        creature_variables: Vec<HashMap<String, CharacterVar>>, extra: Extra)->Self{
+        let mut tree = Self{creatures,creature_properties_tree,creature_variables,extra,index: HashMap::new()};
+        tree.reindex();
+        tree
+    }
     /// Build the tree from a flat character
     /// 
+    /// Properties whose ancestors are missing are kept under placeholder folders, see [`TreeCharacter::build_tree_with`] to find out which
     /// # Examples
     #[cfg_attr(doctest, doc = " ````no_test")]
     /// ```
@@ -169,41 +298,277 @@ impl TreeCharacter{
     /// ````
     #[must_use]
     pub fn build_tree(ch: FlatCharacter)->Self{
-        let creatures = ch.creatures;
-        let mut creature_properties=ch.creature_properties;
-        let creature_variables=ch.creature_variables;
-        creature_properties.sort_by(|a,b|a.partial_cmp(b).expect("unreachable"));
-        let mut creature_properties_tmap:HashMap<String, TreeProp>=HashMap::new();
-        for prop in creature_properties{
-            let path: Vec<String> = prop.ancestors.iter().map(|anc|anc.id.clone()).collect();
-            if let Some(id) =  path.get(1){
-                creature_properties_tmap.entry(id.clone())
-                    .and_modify(|p: &mut TreeProp| 
-                        p.recurse_insert(path,
-                            TreeProp::from_flat_prop(prop)));
-            } else {
-                creature_properties_tmap.insert(prop.id.clone(),TreeProp::from_flat_prop(prop));
+        Self::build_tree_with(ch,OrphanHandling::Placeholders).0
+    }
+    /// Build the tree from a flat character, failing if any property's ancestors are missing
+    /// 
+    /// # Errors
+    /// An [`Error::BrokenLinkage`] for the first orphaned property
+    pub fn try_build_tree(ch: FlatCharacter)->Result<Self,Error>{
+        let (tree,report) = Self::build_tree_with(ch,OrphanHandling::Placeholders);
+        report.orphans.into_iter().next().map_or(Ok(tree),Err)
+    }
+    /// Build the tree from a flat character, reporting any properties whose ancestors are missing
+    /// 
+    /// No property is ever dropped, `orphans` says where to put those that can't go under their real ancestors
+    #[must_use]
+    pub fn build_tree_with(ch: FlatCharacter, orphans: OrphanHandling)->(Self,TreeReport){
+        let mut report = TreeReport::default();
+        let known: HashSet<String> = ch.creature_properties.iter().map(|p|p.id.clone()).collect();
+        let root_id = new_id(&known);
+        let root_order = ch.creature_properties.iter().map(|p|p.order+1).max().unwrap_or_default();
+        // the path to a property is its ancestors without the creature
+        let mut placed: Vec<(Vec<String>,FlatProp)> = ch.creature_properties.into_iter().map(|prop|{
+            let path: Vec<String> = prop.ancestors.iter().filter(|anc|!anc.is_creature()).map(|anc|anc.id.clone()).collect();
+            let Some(missing) = path.iter().rposition(|id|!known.contains(id)) else {
+                return (path,prop);
+            };
+            if missing+1==path.len(){
+                report.orphans.push(Error::BrokenLinkage{id: prop.id.clone(), missing: path[missing].clone()});
+            }
+            match orphans{
+                OrphanHandling::Placeholders=>(path,prop),
+                OrphanHandling::SyntheticRoot=>{
+                    let rest = path[missing+1..].iter().cloned();
+                    (std::iter::once(root_id.clone()).chain(rest).collect(),prop)
+                }
+            }
+        }).collect();
+        // parents are always shallower than their children, so placing shallowest first means real ancestors are in place before they're needed
+        placed.sort_by_key(|(path,prop)|(path.len(),prop.order));
+        let mut creature_properties_tree: Vec<TreeProp>=Vec::new();
+        if orphans==OrphanHandling::SyntheticRoot && !report.orphans.is_empty(){
+            let parent = ch.creatures.first().map(|c|Identifier::creature(c.id.clone())).unwrap_or_default();
+            let name = "Orphaned properties".to_string();
+            insert_ordered(&mut creature_properties_tree,TreeProp{id: root_id.clone(),
+                prop_type: PropType::Folder{name: name.into(), group_stats: Tracked::default(), hide_stats_group: Tracked::default(), tab: Tracked::default(), location: Tracked::default(), extra: Extra::default()},
+                ancestors: vec![parent.clone()], parent, order: root_order, ..TreeProp::default()});
+            report.orphan_root = Some(root_id);
+        }
+        for (path,prop) in placed{
+            let mut siblings = &mut creature_properties_tree;
+            for (depth,id) in path.iter().enumerate(){
+                let pos = if let Some(pos) = siblings.iter().position(|p|&p.id==id) {pos} else {
+                    report.placeholders.push(id.clone());
+                    // the placeholder sits where the property says its ancestor is
+                    let above = prop.ancestors.iter().position(|anc|&anc.id==id).unwrap_or(depth);
+                    let ancestors = prop.ancestors[..above].to_vec();
+                    let placeholder = TreeProp{id: id.clone(), parent: ancestors.last().cloned().unwrap_or_default(),
+                        ancestors, ..TreeProp::default()};
+                    insert_ordered(siblings,placeholder);
+                    siblings.iter().position(|p|&p.id==id).unwrap_or_default()
+                };
+                siblings = &mut siblings[pos].children;
             }
+            insert_ordered(siblings,TreeProp::from_flat_prop(prop));
         }
-        Self{creatures,creature_properties_tmap,creature_variables}
+        // the orphans still point at their missing ancestors until now
+        if let Some(root) = report.orphan_root.as_ref().and_then(|id|creature_properties_tree.iter_mut().find(|p|&p.id==id)){
+            relink(root,root.parent.clone(),root.ancestors.clone());
+        }
+        (Self::new(ch.creatures,creature_properties_tree,ch.creature_variables,ch.extra),report)
     }
-    /// Returns an iterator over references to the roots of the tree
-    #[must_use]
-    pub fn roots(&self)->Roots{
-        self.creature_properties_tmap.values()
+    /// Returns an iterator over references to the roots of the tree, in `order`
+    #[must_use="the roots are only walked when the iterator is used"]
+    pub fn roots(&self)->Roots<'_>{
+        self.creature_properties_tree.iter()
     }
-    /// Returns an iterator over mutable references to the roots of the tree
-    pub fn roots_mut(&mut self)->RootsMut{
-        self.creature_properties_tmap.values_mut()
+    /// Returns an iterator over mutable references to the roots of the tree, in `order`
+    pub fn roots_mut(&mut self)->RootsMut<'_>{
+        self.creature_properties_tree.iter_mut()
     }
-    /// Takes the roots out of the tree, returning them as a list
+    /// Takes the roots out of the tree, returning them as a list in `order`
     /// 
     /// Leaves the interal set empty
     pub fn take_roots(&mut self)->Vec<TreeProp>{
-        std::mem::take(&mut self.creature_properties_tmap).into_values().collect()
+        self.index.clear();
+        std::mem::take(&mut self.creature_properties_tree)
     }
-    /// Add a root to the tree
+    /// Add a root to the tree, after any roots with the same `order`
     pub fn add_root(&mut self, prop: TreeProp){
-        self.creature_properties_tmap.insert(prop.id.clone(), prop);
+        let pos = insert_ordered(&mut self.creature_properties_tree,prop);
+        index_siblings(&mut self.index,&self.creature_properties_tree,&[],pos);
+    }
+    /// Rebuild the index from the tree
+    /// 
+    /// Needed after adding, removing or reordering properties through [`TreeProp`] methods, lookups can't find them until then
+    pub fn reindex(&mut self){
+        self.index.clear();
+        index_siblings(&mut self.index,&self.creature_properties_tree,&[],0);
     }
-}
\ No newline at end of file
+    /// Whether a property with this id is in the tree
+    #[must_use]
+    pub fn contains(&self, id: &str)->bool{
+        self.get(id).is_some()
+    }
+    /// Find a property anywhere in the tree
+    #[must_use]
+    pub fn get(&self, id: &str)->Option<&TreeProp>{
+        let (first,rest) = self.index.get(id)?.split_first()?;
+        let mut prop = self.creature_properties_tree.get(*first)?;
+        for pos in rest{
+            prop = prop.children.get(*pos)?;
+        }
+        // a stale index can point at the wrong property
+        (prop.id==id).then_some(prop)
+    }
+    /// Find a property anywhere in the tree, to change it
+    /// 
+    /// Changing the `id` or `order` of the property, or its children, will leave the tree out of date, use [`TreeCharacter::reindex`] or [`TreeCharacter::sort`] afterwards
+    pub fn get_mut(&mut self, id: &str)->Option<&mut TreeProp>{
+        let (first,rest) = self.index.get(id)?.split_first()?;
+        let mut prop = self.creature_properties_tree.get_mut(*first)?;
+        for pos in rest{
+            prop = prop.children.get_mut(*pos)?;
+        }
+        (prop.id==id).then_some(prop)
+    }
+    /// The parent of a property, or `None` if it is a root or not in the tree
+    #[must_use]
+    pub fn parent_of(&self, id: &str)->Option<&TreeProp>{
+        let mut path = self.path_to(id)?;
+        path.pop();
+        path.pop()
+    }
+    /// Every property from the root down to `id`, ending with the property itself
+    #[must_use]
+    pub fn path_to(&self, id: &str)->Option<Vec<&TreeProp>>{
+        let positions = self.index.get(id)?;
+        let mut siblings = &self.creature_properties_tree;
+        let mut path = Vec::with_capacity(positions.len());
+        for pos in positions{
+            let prop = siblings.get(*pos)?;
+            path.push(prop);
+            siblings = &prop.children;
+        }
+        path.last().is_some_and(|prop|prop.id==id).then_some(path)
+    }
+    /// Take a property, and everything under it, out of the tree
+    pub fn remove(&mut self, id: &str)->Option<TreeProp>{
+        let positions = self.index.get(id)?;
+        let (last,parent) = positions.split_last()?;
+        let (last,parent) = (*last,parent.to_vec());
+        let siblings = siblings_at(&mut self.creature_properties_tree,&parent)?;
+        if siblings.get(last)?.id!=id{
+            return None;
+        }
+        let removed = siblings.remove(last);
+        unindex_subtree(&mut self.index,&removed);
+        // the siblings after it have moved up one
+        index_siblings(&mut self.index,siblings,&parent,last);
+        Some(removed)
+    }
+    /// Move a property, along with everything under it, to be the child of `new_parent` (or a root if `None`) at `position` among its new siblings
+    /// 
+    /// The `parent` and `ancestors` of the moved properties are rewritten to match their new place.
+    /// Like dicecloud, `order` is shared across the whole character, so every property is renumbered in tree order afterwards
+    /// 
+    /// # Errors
+    /// If either property isn't in the tree, or `new_parent` is the property being moved or inside it
+    pub fn move_node(&mut self, id: &str, new_parent: Option<&str>, position: usize)->Result<(),Error>{
+        let not_found = |id: &str|Error::NotFound{id: id.to_string()};
+        let prop = self.get(id).ok_or_else(||not_found(id))?;
+        let creature = prop.ancestors.iter().find(|anc|anc.is_creature()).cloned()
+            .or_else(||self.creatures.first().map(|c|Identifier::creature(c.id.clone())))
+            .unwrap_or_default();
+        let (parent,ancestors) = if let Some(new_parent) = new_parent{
+            let parent_prop = self.get(new_parent).ok_or_else(||not_found(new_parent))?;
+            // anything under the property has its position as a prefix
+            if self.index.get(new_parent).zip(self.index.get(id)).is_some_and(|(under,moved)|under.starts_with(moved)){
+                return Err(Error::CyclicMove{id: id.to_string(), parent: new_parent.to_string()});
+            }
+            let parent = Identifier::property(new_parent);
+            let mut ancestors = parent_prop.ancestors.clone();
+            ancestors.push(parent.clone());
+            (parent,ancestors)
+        } else {
+            (creature.clone(),vec![creature])
+        };
+        let mut prop = self.remove(id).ok_or_else(||not_found(id))?;
+        relink(&mut prop,parent,ancestors);
+        // removing the property may have moved the new parent up one
+        let parent_positions = match new_parent{
+            Some(new_parent)=>self.index.get(new_parent).ok_or_else(||not_found(new_parent))?.clone(),
+            None=>Vec::new()
+        };
+        let siblings = siblings_at(&mut self.creature_properties_tree,&parent_positions).ok_or_else(||not_found(new_parent.unwrap_or(id)))?;
+        let position = position.min(siblings.len());
+        siblings.insert(position,prop);
+        index_siblings(&mut self.index,siblings,&parent_positions,position);
+        // dicecloud sorts the whole character by `order`, so it has to follow the tree's pre-order
+        let mut order = 0;
+        for root in &mut self.creature_properties_tree{
+            renumber(root,&mut order);
+        }
+        Ok(())
+    }
+    /// Re-sort the roots and every property under them by `order`
+    /// 
+    /// Only needed after changing `order` through [`TreeCharacter::roots_mut`] or [`TreeProp::children_mut`]
+    pub fn sort(&mut self){
+        self.creature_properties_tree.sort_by_key(|p|p.order);
+        self.creature_properties_tree.iter_mut().for_each(TreeProp::sort_children);
+        self.reindex();
+    }
+}
+/// Index `siblings[from..]`, and everything under them, given the positions down to their parent
+fn index_siblings(index: &mut HashMap<String,Vec<usize>>, siblings: &[TreeProp], parent: &[usize], from: usize){
+    for (pos,prop) in siblings.iter().enumerate().skip(from){
+        let mut positions = parent.to_vec();
+        positions.push(pos);
+        index_siblings(index,&prop.children,&positions,0);
+        index.insert(prop.id.clone(),positions);
+    }
+}
+fn unindex_subtree(index: &mut HashMap<String,Vec<usize>>, prop: &TreeProp){
+    index.remove(&prop.id);
+    for child in &prop.children{
+        unindex_subtree(index,child);
+    }
+}
+/// The children of the property at `positions`, or the roots if it is empty
+fn siblings_at<'a>(roots: &'a mut Vec<TreeProp>, positions: &[usize])->Option<&'a mut Vec<TreeProp>>{
+    let mut siblings = roots;
+    for pos in positions{
+        siblings = &mut siblings.get_mut(*pos)?.children;
+    }
+    Some(siblings)
+}
+/// The characters meteor, and so dicecloud, makes ids out of
+const ID_ALPHABET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTWXYZabcdefghijkmnopqrstuvwxyz";
+/// A new 17 character id in dicecloud's style that isn't in `taken`
+///
+/// Picked from a hash of the ids already taken, so building the same character twice gives the same id
+fn new_id(taken: &HashSet<String>)->String{
+    let mut hasher = DefaultHasher::new();
+    taken.iter().collect::<BTreeSet<_>>().hash(&mut hasher);
+    loop{
+        let id: String = (0..17).map(|_|{
+            hasher.write_u8(0);
+            let pick = hasher.finish()%ID_ALPHABET.len() as u64;
+            char::from(ID_ALPHABET[usize::try_from(pick).unwrap_or_default()])
+        }).collect();
+        if !taken.contains(&id){
+            return id;
+        }
+    }
+}
+/// Point a property at its new parent, and its descendants at their new ancestors
+fn relink(prop: &mut TreeProp, parent: Identifier, ancestors: Vec<Identifier>){
+    let me = Identifier::property(prop.id.clone());
+    let mut child_ancestors = ancestors.clone();
+    child_ancestors.push(me.clone());
+    prop.parent = parent;
+    prop.ancestors = ancestors;
+    for child in &mut prop.children{
+        relink(child,me.clone(),child_ancestors.clone());
+    }
+}
+/// Give a property and everything under it consecutive `order`s, in pre-order
+fn renumber(prop: &mut TreeProp, order: &mut usize){
+    prop.order = *order;
+    *order+=1;
+    for child in &mut prop.children{
+        renumber(child,order);
+    }
+}
@@ -0,0 +1,56 @@
+//! Working out which triggers fire, so automation can be simulated outside of dicecloud
+use crate::data_models::flat_model::{FlatCharacter, FlatProp};
+use crate::data_models::generic_model::{ExtraTag, ExtraTagOperation, PropType, TriggerEvent, TriggerTiming};
+
+/// Whether a property with `tags` is targeted by `target_tags` and `extra_tags`
+///
+/// It must have all the target tags, or all the tags of one of the `OR` extra tags, and none of the tags of a `NOT` extra tag.
+/// With no target tags and no `OR` extra tags every property is targeted
+fn targets(target_tags: &[String], extra_tags: &[ExtraTag], tags: &[&String])->bool{
+    let has_all = |wanted: &[String]|wanted.iter().all(|tag|tags.contains(&tag));
+    let alternatives: Vec<&ExtraTag> = extra_tags.iter().filter(|extra|extra.operation==ExtraTagOperation::Or).collect();
+    let included = if target_tags.is_empty() && alternatives.is_empty() {true} else {
+        (!target_tags.is_empty() && has_all(target_tags)) || alternatives.iter().any(|extra|has_all(&extra.tags))
+    };
+    let excluded = extra_tags.iter().filter(|extra|extra.operation==ExtraTagOperation::Not)
+        .any(|extra|extra.tags.iter().any(|tag|tags.contains(&tag)));
+    included && !excluded
+}
+impl FlatCharacter{
+    /// The active triggers that fire at `timing` when `event` happens, to `property` if the event happens to one
+    ///
+    /// `anyRest` triggers fire for both kinds of rest. For events that happen to a property, triggers only fire if the property matches their tags,
+    /// and for `doActionProperty`, their `action_property_type`.
+    /// Trigger conditions aren't evaluated, use an [`Evaluator`][crate::Evaluator] on them if needed.
+    /// Triggers come back in the order they are in `creature_properties`
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::{FlatCharacter, TriggerEvent, TriggerTiming};
+    /// let character = FlatCharacter::default();
+    /// assert!(character.triggers_for(&TriggerEvent::LongRest, &TriggerTiming::After, None).is_empty());
+    /// ```
+    #[must_use]
+    pub fn triggers_for(&self, event: &TriggerEvent, timing: &TriggerTiming, property: Option<&FlatProp>)->Vec<&FlatProp>{
+        self.active_properties().filter(|prop|{
+            let PropType::Trigger{event: listening,timing: when,action_property_type,target_tags,extra_tags,..} = &prop.prop_type else {
+                return false;
+            };
+            if !listening.fires_on(event) || when!=timing{
+                return false;
+            }
+            if !event.has_property(){
+                return true;
+            }
+            let Some(property) = property else {
+                return false;
+            };
+            if *event==TriggerEvent::DoActionProperty
+                && action_property_type.as_deref().is_some_and(|typ|typ!=property.prop_type.type_name()){
+                return false;
+            }
+            let tags: Vec<&String> = property.tags.iter().chain(property.library_tags.iter()).collect();
+            targets(target_tags,extra_tags,&tags)
+        }).collect()
+    }
+}
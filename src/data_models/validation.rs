@@ -0,0 +1,125 @@
+//! Checking that the properties of a character link up into a proper tree
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::data_models::flat_model::{FlatCharacter, FlatProp};
+use crate::data_models::generic_model::{Collection, Identifier};
+
+/// A structural problem found by [`FlatCharacter::validate`]
+#[derive(PartialEq,Eq,Debug,Clone)]
+pub enum Finding{
+    /// `parent` isn't the last of the property's `ancestors`
+    ParentMismatch{id: String, parent: String, last_ancestor: Option<String>},
+    /// One of the property's ancestors (or its parent) isn't part of the character
+    MissingAncestor{id: String, missing: String},
+    /// More than one property has the same `order`
+    OrderCollision{order: usize, ids: Vec<String>},
+    /// Following parents from these properties leads back round to them, or a property is listed among its own ancestors
+    Cycle{ids: Vec<String>},
+    /// An identifier points at a collection other than the one it should, `creatures` for the first ancestor and `creatureProperties` after
+    WrongCollection{id: String, target: String, collection: Collection, expected: Collection},
+}
+impl Finding{
+    /// The ids of the properties at fault
+    #[must_use]
+    pub fn ids(&self)->Vec<&str>{
+        match self{
+            Self::ParentMismatch{id,..}|Self::MissingAncestor{id,..}|Self::WrongCollection{id,..}=>vec![id],
+            Self::OrderCollision{ids,..}|Self::Cycle{ids}=>ids.iter().map(String::as_str).collect()
+        }
+    }
+}
+impl fmt::Display for Finding{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            Self::ParentMismatch{id,parent,last_ancestor:Some(last)}=>write!(f,"property {id} has parent {parent} but its last ancestor is {last}"),
+            Self::ParentMismatch{id,parent,last_ancestor:None}=>write!(f,"property {id} has parent {parent} but no ancestors"),
+            Self::MissingAncestor{id,missing}=>write!(f,"property {id} refers to {missing}, which is not part of the character"),
+            Self::OrderCollision{order,ids}=>write!(f,"properties {} all have order {order}",ids.join(", ")),
+            Self::Cycle{ids}=>write!(f,"properties {} are their own ancestors",ids.join(", ")),
+            Self::WrongCollection{id,target,collection,expected}=>
+                write!(f,"property {id} refers to {target} in {collection}, expected {expected}")
+        }
+    }
+}
+/// The collection an identifier at this position in the ancestors should be in
+const fn expected_collection(position: usize)->Collection{
+    if position==0 {Collection::Creatures} else {Collection::CreatureProperties}
+}
+fn check_links(prop: &FlatProp, creatures: &HashSet<&str>, props: &HashMap<&str,&FlatProp>, findings: &mut Vec<Finding>){
+    let id = &prop.id;
+    let last = prop.ancestors.last();
+    if last.map(|l|&l.id)!=Some(&prop.parent.id){
+        findings.push(Finding::ParentMismatch{id: id.clone(), parent: prop.parent.id.clone(), last_ancestor: last.map(|l|l.id.clone())});
+    }
+    let mut checked: HashSet<&str> = HashSet::new();
+    let position_of_parent = prop.ancestors.len().saturating_sub(1);
+    let links = prop.ancestors.iter().enumerate().chain(std::iter::once((position_of_parent,&prop.parent)));
+    for (position,Identifier{id: target,collection,..}) in links{
+        if !checked.insert(target){
+            continue;
+        }
+        let expected = expected_collection(position);
+        if *collection!=expected{
+            findings.push(Finding::WrongCollection{id: id.clone(), target: target.clone(), collection: collection.clone(), expected});
+        }
+        if !creatures.contains(target.as_str()) && !props.contains_key(target.as_str()){
+            findings.push(Finding::MissingAncestor{id: id.clone(), missing: target.clone()});
+        }
+    }
+}
+/// Follow parents from each property, reporting each loop once
+fn find_cycles(character: &FlatCharacter, props: &HashMap<&str,&FlatProp>, findings: &mut Vec<Finding>){
+    let mut reported: HashSet<&str> = HashSet::new();
+    for prop in &character.creature_properties{
+        if prop.ancestors.iter().any(|a|a.id==prop.id) && reported.insert(&prop.id){
+            findings.push(Finding::Cycle{ids: vec![prop.id.clone()]});
+            continue;
+        }
+        let mut chain: Vec<&str> = vec![&prop.id];
+        let mut current = prop;
+        while let Some(parent) = props.get(current.parent.id.as_str()){
+            if let Some(start) = chain.iter().position(|id|*id==parent.id){
+                let mut ids: Vec<&str> = chain[start..].to_vec();
+                ids.sort_unstable();
+                if ids.iter().all(|id|reported.insert(id)){
+                    findings.push(Finding::Cycle{ids: ids.into_iter().map(str::to_string).collect()});
+                }
+                break;
+            }
+            if reported.contains(parent.id.as_str()){
+                break;
+            }
+            chain.push(&parent.id);
+            current = parent;
+        }
+    }
+}
+impl FlatCharacter{
+    /// Check that the properties link up into a proper tree
+    ///
+    /// Looks for parents that disagree with ancestors, ancestors that aren't part of the character, properties sharing an `order`,
+    /// loops in the ancestry, and identifiers pointing at the wrong collection. An empty list means none were found
+    ///
+    /// # Examples
+    /// ```
+    /// use dicecloud_models::FlatCharacter;
+    /// assert!(FlatCharacter::default().validate().is_empty());
+    /// ```
+    #[must_use]
+    pub fn validate(&self)->Vec<Finding>{
+        let mut findings = Vec::new();
+        let creatures: HashSet<&str> = self.creatures.iter().map(|c|c.id.as_str()).collect();
+        let props: HashMap<&str,&FlatProp> = self.creature_properties.iter().map(|p|(p.id.as_str(),p)).collect();
+        for prop in &self.creature_properties{
+            check_links(prop,&creatures,&props,&mut findings);
+        }
+        let mut orders: BTreeMap<usize,Vec<String>> = BTreeMap::new();
+        for prop in &self.creature_properties{
+            orders.entry(prop.order).or_default().push(prop.id.clone());
+        }
+        findings.extend(orders.into_iter().filter(|(_,ids)|ids.len()>1).map(|(order,ids)|Finding::OrderCollision{order,ids}));
+        find_cycles(self,&props,&mut findings);
+        findings
+    }
+}
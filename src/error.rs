@@ -0,0 +1,103 @@
+#![warn(clippy::pedantic,clippy::nursery,clippy::unwrap_used,clippy::perf)]
+//! The error type shared by the whole crate
+use core::fmt;
+
+/// A `Result` using this crate's [`Error`]
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while reading or working with a character
+///
+/// Where possible the `_id` of the offending object and the path to the offending field in the json (like `creatureProperties[3].libraryTags`) are included
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error{
+    /// The input was not valid json at all
+    #[cfg(feature="serde_json")]
+    Json(serde_json::Error),
+    /// A property's `type` tag isn't one of the [`PropType`][crate::PropType] variants
+    UnknownPropType{id: Option<String>, path: String, typ: String},
+    /// A required field was missing
+    MissingField{id: Option<String>, path: String, field: String},
+    /// A field was present but didn't have the expected shape
+    InvalidField{id: Option<String>, path: String, message: String},
+    /// A property refers to a parent or ancestor which isn't part of the character
+    BrokenLinkage{id: String, missing: String},
+    /// No property has this id
+    NotFound{id: String},
+    /// A property can't be moved under itself or one of its descendants
+    CyclicMove{id: String, parent: String},
+}
+impl Error{
+    /// The `_id` of the object which caused the error, if known
+    #[must_use]
+    pub fn id(&self)->Option<&str>{
+        match self{
+            Self::UnknownPropType{id,..}|Self::MissingField{id,..}|Self::InvalidField{id,..}=>id.as_deref(),
+            Self::BrokenLinkage{id,..}|Self::NotFound{id}|Self::CyclicMove{id,..}=>Some(id),
+            #[cfg(feature="serde_json")]
+            Self::Json(_)=>None
+        }
+    }
+    /// The path to the offending field in the json, if known
+    #[must_use]
+    pub fn path(&self)->Option<&str>{
+        match self{
+            Self::UnknownPropType{path,..}|Self::MissingField{path,..}|Self::InvalidField{path,..}=>Some(path),
+            _=>None
+        }
+    }
+    /// Turn a serde error for the field at `path` into a more specific error
+    #[cfg(feature="serde_json")]
+    pub(crate) fn at(err: &impl fmt::Display, path: String, id: Option<String>)->Self{
+        let message = err.to_string();
+        // serde reports these as "missing field `name`"
+        let quoted = ||message.split('`').nth(1).unwrap_or_default().to_string();
+        if message.starts_with("missing field"){
+            Self::MissingField{id, path, field: quoted()}
+        } else {
+            Self::InvalidField{id, path, message}
+        }
+    }
+}
+impl fmt::Display for Error{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |f: &mut fmt::Formatter<'_>,path: &str,id: &Option<String>|match id{
+            Some(id)=>write!(f,"{path} (_id {id})"),
+            None=>write!(f,"{path}")
+        };
+        match self{
+            #[cfg(feature="serde_json")]
+            Self::Json(e)=>write!(f,"invalid json: {e}"),
+            Self::UnknownPropType{id,path,typ}=>{
+                at(f,path,id)?;
+                write!(f,": unknown property type `{typ}`")
+            },
+            Self::MissingField{id,path,field}=>{
+                at(f,path,id)?;
+                write!(f,": missing field `{field}`")
+            },
+            Self::InvalidField{id,path,message}=>{
+                at(f,path,id)?;
+                write!(f,": {message}")
+            },
+            Self::BrokenLinkage{id,missing}=>write!(f,"property {id} refers to {missing}, which is not part of the character"),
+            Self::NotFound{id}=>write!(f,"no property has the id {id}"),
+            Self::CyclicMove{id,parent}=>write!(f,"property {id} can't be moved under {parent}, which is inside it")
+        }
+    }
+}
+impl std::error::Error for Error{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self{
+            #[cfg(feature="serde_json")]
+            Self::Json(e)=>Some(e),
+            _=>None
+        }
+    }
+}
+#[cfg(feature="serde_json")]
+impl From<serde_json::Error> for Error{
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
@@ -12,11 +12,24 @@
 //! The key structs this provides are [`FlatCharacter`][crate::FlatCharacter] and [`FlatProp`][crate::FlatProp]
 //! 
 //! This crate also allows converting the standard flat representation to a [`TreeCharacter`][crate::TreeCharacter]
+//!
+//! Calculations can be re-evaluated offline with the [`Evaluator`][crate::calculation::eval::Evaluator]
 pub mod data_models;
+pub mod calculation;
+pub mod error;
 
 pub use crate::data_models::generic_model::*;
 pub use crate::data_models::flat_model::{FlatProp,FlatCharacter};
-pub use crate::data_models::tree_model::{TreeProp,TreeCharacter};
+pub use crate::data_models::tree_model::{TreeProp,TreeCharacter,OrphanHandling,TreeReport};
+pub use crate::data_models::traversal::Visit;
+pub use crate::data_models::validation::Finding;
+pub use crate::data_models::index::PropIndex;
+pub use crate::data_models::spell::{CastingTime,SpellRange,SpellDuration};
+pub use crate::error::Error;
+#[cfg(feature="serde_json")]
+pub use crate::data_models::stream::{CharacterPart,CharacterReader};
+pub use crate::calculation::eval::{Evaluator,Scope};
+pub use crate::calculation::dice::{DiceRng,DiceRoll,SeededRng};
 #[cfg(all(test,feature="serde_json"))]
 mod tests {
     use super::*;
@@ -35,7 +48,7 @@ mod tests {
         assert_eq!(deser1,PropVal::Number(1));
         assert_eq!(deser2,PropVal::Str("test".to_string()));
         assert_eq!(deser3,PropVal::Boolean(true));
-        assert_eq!(deser4,PropVal::Fraction(1.5.into()));
+        assert_eq!(deser4,PropVal::Fraction(1.5));
         assert_eq!(deser5,PropVal::None(None));
     }
     #[test]
@@ -64,7 +77,7 @@ mod tests {
     fn parse_error(){
         let tst = "{\"type\": \"info\",\"message\": \"dne not found, set to 0\"}";
         let deser: ParseError = serde_json::from_str(tst).unwrap();
-        assert_eq!(deser,ParseError{typ: "info".to_string(),message: "dne not found, set to 0".to_string()});
+        assert_eq!(deser,ParseError::new("info","dne not found, set to 0"));
     }
     #[test]
     fn calculation(){
@@ -79,11 +92,11 @@ mod tests {
         let cond = ParseNode::Symbol{name: "dne".to_string()};
         let cons = ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(1)};
         let alt = ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(0)};
-        let err= ParseError{typ: "info".to_string(),message: "dne not found, set to 0".to_string()};
+        let err= ParseError::new("info","dne not found, set to 0");
         let calc = Calculation{calculation: "dne ? 1 : 0".to_string(),key:"baseValue".to_string(),
             typ: "_calculation".to_string(),hash:5843567941511658,parse_node: ParseNode::If{condition: Box::new(cond),
                 consequent: Box::new(cons),alternative: Box::new(alt)},errors: vec![err],value: PropVal::Number(0),
-                base_value: None, effects: vec![]};
+                base_value: Tracked::default(), effects: Tracked::default(),extra: serde_json::from_str("{\"parseError\": null}").unwrap()};
         assert_eq!(deser, calc);
     }
-}
\ No newline at end of file
+}
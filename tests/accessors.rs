@@ -0,0 +1,79 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{ActionType, Collection, Effect, EffectOperation, ExtraTag, ExtraTagOperation, FlatCharacter, Identifier, PropKind, PropType, Reset, Target};
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+fn prop_type<'a>(character: &'a FlatCharacter, id: &str)->&'a PropType{
+    &character.creature_properties.iter().find(|p|p.id==id).unwrap().prop_type
+}
+#[test]
+fn shared_fields(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let strength = prop_type(&character,"strengthId");
+    assert_eq!((strength.kind(),strength.variable_name()),(PropKind::Attribute,Some("strength")));
+    let action = prop_type(&character,"actionId");
+    assert_eq!(action.kind(),PropKind::Action);
+    assert!(action.name().is_some());
+    assert_eq!(action.target(),Some(&Target::SingleTarget));
+    assert!(matches!(action,PropType::Action{action_type: ActionType::Attack,reset,..} if reset.is_none()));
+    let note = prop_type(&character,"noteId");
+    assert!(note.summary().is_some());
+    assert_eq!(note.target(),None);
+    assert!(!note.is_silent());
+    let branch = prop_type(&character,"branchId");
+    assert_eq!((branch.name(),branch.kind().as_str()),(None,"branch"));
+    assert_eq!(prop_type(&character,"toggleId").variable_name(),Some("raging"));
+    for prop in &character.creature_properties{
+        assert_eq!(prop.prop_type.kind().as_str(),prop.prop_type.type_name());
+        assert_eq!(serde_json::to_value(prop.prop_type.kind()).unwrap(),serde_json::to_value(prop).unwrap()["type"]);
+    }
+}
+#[test]
+fn kinds(){
+    assert_eq!(serde_json::from_str::<PropKind>("\"spellList\"").unwrap(),PropKind::SpellList);
+    let spaceship = serde_json::from_str::<PropKind>("\"spaceship\"").unwrap();
+    assert_eq!(spaceship,PropKind::Unknown("spaceship".to_string()));
+    // unknown types are written back out as they were read
+    assert_eq!((serde_json::to_string(&spaceship).unwrap(),spaceship.as_str()),("\"spaceship\"".to_string(),"spaceship"));
+    assert_eq!(PropKind::DamageMultiplier.to_string(),"damageMultiplier");
+    let (character,_) = FlatCharacter::from_json_lenient("{\"creatures\": [],\"creatureVariables\": [],\"creatureProperties\": [
+        {\"_id\": \"new\",\"type\": \"spaceship\",\"name\": \"Ship\",\"tags\": [],\"order\": 0,\"parent\": {\"id\": \"c\",\"collection\": \"creatures\"},
+            \"ancestors\": [{\"id\": \"c\",\"collection\": \"creatures\"}]}]}").unwrap();
+    let unknown = &character.creature_properties[0].prop_type;
+    assert_eq!((unknown.kind(),unknown.type_name(),unknown.name()),(PropKind::Unknown("spaceship".to_string()),"spaceship",None));
+}
+#[test]
+fn dicecloud_strings(){
+    assert_eq!(serde_json::to_string(&Target::Self_).unwrap(),"\"self\"");
+    assert_eq!(serde_json::from_str::<Reset>("\"longRest\"").unwrap(),Reset::LongRest);
+    assert_eq!(serde_json::from_str::<ActionType>("\"bonus\"").unwrap(),ActionType::Bonus);
+    let odd: ActionType = serde_json::from_str("\"lair\"").unwrap();
+    assert_eq!(odd,ActionType::Other("lair".to_string()));
+    assert_eq!(serde_json::to_string(&odd).unwrap(),"\"lair\"");
+    assert_eq!(Target::from("multipleTargets"),Target::MultipleTargets);
+    assert_eq!(Reset::ShortRest.to_string(),"shortRest");
+}
+#[test]
+fn effect_operations(){
+    let effect: Effect = serde_json::from_str("{\"_id\": \"e\",\"operation\": \"passiveAdd\",\"amount\": {\"value\": 5}}").unwrap();
+    assert_eq!(effect.operation,EffectOperation::PassiveAdd);
+    assert!(effect.operation.is_numeric());
+    assert_eq!(serde_json::to_value(&effect).unwrap()["operation"],"passiveAdd");
+    let kinds: Vec<(bool,bool,bool)> = [EffectOperation::Mul,EffectOperation::Disadvantage,EffectOperation::Conditional,EffectOperation::from("glow")]
+        .iter().map(|op|(op.is_numeric(),op.is_roll(),op.is_textual())).collect();
+    assert_eq!(kinds,vec![(true,false,false),(false,true,false),(false,false,true),(false,false,false)]);
+    let tag: ExtraTag = serde_json::from_str("{\"_id\": \"t\",\"operation\": \"NOT\",\"tags\": [\"cursed\"]}").unwrap();
+    assert_eq!(tag.operation,ExtraTagOperation::Not);
+    assert_eq!(serde_json::to_value(&tag).unwrap()["operation"],"NOT");
+}
+#[test]
+fn collections(){
+    let ids: Vec<Identifier> = serde_json::from_str("[{\"id\": \"c\",\"collection\": \"creatures\"},{\"id\": \"p\",\"collection\": \"creatureProperties\"},
+        {\"id\": \"l\",\"collection\": \"libraryNodes\"},{\"id\": \"x\",\"collection\": \"tabletops\"}]").unwrap();
+    assert_eq!(ids.iter().map(|i|i.collection.clone()).collect::<Vec<_>>(),
+        vec![Collection::Creatures,Collection::CreatureProperties,Collection::LibraryNodes,Collection::Unknown("tabletops".to_string())]);
+    assert_eq!(ids.iter().map(|i|(i.is_creature(),i.is_property())).collect::<Vec<_>>(),vec![(true,false),(false,true),(false,false),(false,false)]);
+    assert_eq!(ids[0],Identifier::creature("c"));
+    assert_eq!(ids[1],Identifier::property("p"));
+    assert_eq!(serde_json::to_value(&ids[3]).unwrap()["collection"],"tabletops");
+}
@@ -0,0 +1,41 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{FlatCharacter, PropType, TreeCharacter};
+
+mod common;
+use common::with_prop;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+fn flat_ids(character: &FlatCharacter)->Vec<&str>{
+    character.active_properties().map(|p|p.id.as_str()).collect()
+}
+#[test]
+fn flags_are_combined(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let branch = character.creature_properties.iter().find(|p|p.id=="branchId").unwrap();
+    assert!(!branch.is_active());
+    assert!(!flat_ids(&character).contains(&"branchId"));
+    with_prop(&mut character,"itemId",|p|*p.removed=true);
+    with_prop(&mut character,"multiplierId",|p|*p.deactivated_by_toggle=Some(true));
+    with_prop(&mut character,"strengthId",|p|*p.deactivated_by_ancestor=Some(false));
+    with_prop(&mut character,"toggleId",|p|if let PropType::Toggle{deactivated_by_self,..} = &mut p.prop_type{
+        **deactivated_by_self = true;
+    });
+    assert_eq!(flat_ids(&character),vec!["strengthId","featuresFolderId","noteId","actionId"]);
+}
+#[test]
+fn inactive_ancestors_hide_descendants(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    with_prop(&mut character,"featuresFolderId",|p|*p.inactive=true);
+    let note = character.creature_properties.iter().find(|p|p.id=="noteId").unwrap();
+    assert!(note.is_active());
+    let flat: Vec<String> = flat_ids(&character).into_iter().map(str::to_string).collect();
+    assert!(!flat.contains(&"noteId".to_string()));
+    let tree = TreeCharacter::build_tree(character);
+    let mut from_tree: Vec<String> = tree.active_properties().map(|p|p.id.clone()).collect();
+    assert_eq!(from_tree,vec!["strengthId","actionId","itemId","toggleId","multiplierId"]);
+    from_tree.sort();
+    let mut flat = flat;
+    flat.sort();
+    assert_eq!(from_tree,flat);
+}
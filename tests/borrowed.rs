@@ -0,0 +1,58 @@
+#![cfg(feature="serde_json")]
+use std::borrow::Cow;
+
+use dicecloud_models::data_models::borrowed_model::BorrowedFlatCharacter;
+use dicecloud_models::{Error, FlatCharacter, PropVal};
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn borrowed_converts_to_owned(){
+    let borrowed = BorrowedFlatCharacter::from_json(CHARACTER).unwrap();
+    assert_eq!(borrowed.into_owned().unwrap(),FlatCharacter::from_json(CHARACTER).unwrap());
+    let borrowed = BorrowedFlatCharacter::from_json(CHARACTER).unwrap();
+    let owned = FlatCharacter::from_json(CHARACTER).unwrap();
+    for (b,o) in borrowed.creature_properties.into_iter().zip(owned.creature_properties){
+        assert_eq!(b.into_owned().unwrap(),o);
+    }
+}
+#[test]
+fn strings_are_borrowed(){
+    let character = BorrowedFlatCharacter::from_json(CHARACTER).unwrap();
+    let folder = &character.creature_properties[1];
+    assert!(matches!(folder.id,Cow::Borrowed(_)));
+    assert!(matches!(folder.name,Some(Cow::Borrowed(_))));
+    assert!(folder.tags.iter().all(|t|matches!(t,Cow::Borrowed(_))));
+    let icon = folder.icon.as_ref().unwrap();
+    assert!(matches!(icon.name,Cow::Borrowed(_)));
+    // the svg has escaped quotes, so has to be copied
+    assert!(matches!(icon.shape,Cow::Owned(_)));
+    assert_eq!(&*icon.shape,"<svg viewBox=\"0 0 512 512\"></svg>");
+    assert_eq!(&icon.clone().into_owned().unwrap(),FlatCharacter::from_json(CHARACTER).unwrap().creature_properties[1].icon.as_ref().unwrap());
+}
+#[test]
+fn typed_views(){
+    let character = BorrowedFlatCharacter::from_json(CHARACTER).unwrap();
+    let action = &character.creature_properties[3];
+    let attack = action.calculation("attackRoll").unwrap();
+    assert!(matches!(attack.calculation,Cow::Borrowed(_)));
+    assert_eq!(&*attack.calculation,"strength.modifier + 2");
+    assert_eq!(attack.clone().into_owned().unwrap().value,PropVal::Number(4));
+    assert!(action.calculation("name").is_none());
+    let summary = character.creature_properties[2].summary().unwrap();
+    assert_eq!(&*summary.value,"Grew up near 14 rivers");
+    assert_eq!(summary.into_owned().unwrap().inline_calculations.len(),1);
+    assert_eq!(character.get("exportedAt").map(|v|v.get()),Some("\"2024-01-01T00:00:00.000Z\""));
+    assert!(character.get("missing").is_none());
+}
+#[test]
+fn conversion_errors_are_located(){
+    let broken = CHARACTER.replacen("\"type\": \"toggle\"","\"type\": \"spaceship\"",1);
+    let err = BorrowedFlatCharacter::from_json(&broken).unwrap().into_owned().unwrap_err();
+    assert!(matches!(&err,Error::UnknownPropType{typ,..} if typ=="spaceship"),"{err}");
+    assert_eq!((err.id(),err.path()),(Some("toggleId"),Some("creatureProperties[6].type")));
+    let broken = CHARACTER.replacen("\"weight\": 10.5","\"weight\": \"heavy\"",1);
+    let err = BorrowedFlatCharacter::from_json(&broken).unwrap().into_owned().unwrap_err();
+    assert!(matches!(&err,Error::InvalidField{..}),"{err}");
+    assert_eq!(err.path(),Some("creatureProperties[4]"));
+}
@@ -0,0 +1,67 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+use dicecloud_models::{DamageType, FlatCharacter, FlatProp, PropType, TreeProp};
+use serde_json::{json, Value};
+
+/// Anything with a property id, so [`ids`] works on flat and tree properties alike
+pub trait HasId{
+    fn id(&self)->&str;
+}
+impl HasId for FlatProp{
+    fn id(&self)->&str{
+        &self.id
+    }
+}
+impl HasId for TreeProp{
+    fn id(&self)->&str{
+        &self.id
+    }
+}
+impl<T: HasId> HasId for &T{
+    fn id(&self)->&str{
+        (**self).id()
+    }
+}
+/// The ids of `props`, in order
+pub fn ids<'a, P: HasId+'a>(props: impl IntoIterator<Item=&'a P>)->Vec<&'a str>{
+    props.into_iter().map(HasId::id).collect()
+}
+/// Change the property with this id in place
+pub fn with_prop(character: &mut FlatCharacter, id: &str, f: impl FnOnce(&mut FlatProp)){
+    f(character.creature_properties.iter_mut().find(|p|p.id==id).unwrap());
+}
+
+/// `json` with `fields` added on top, replacing any it already had
+pub fn with_fields(mut json: Value, fields: &Value)->Value{
+    json.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+    json
+}
+/// A property of type `typ` under the fixture's creature, with `fields` on top of the ones every property has
+pub fn prop(id: &str, typ: &str, fields: &Value)->Value{
+    with_fields(json!({"_id": id, "type": typ, "name": id, "tags": [], "order": 100,
+        "parent": {"id": "creatureId1", "collection": "creatures"}, "ancestors": [{"id": "creatureId1", "collection": "creatures"}]}),fields)
+}
+/// A concentration ritual spell, with `extra` on top
+pub fn spell(extra: &Value)->Value{
+    with_fields(prop("spellId","spell",&json!({"name": "Detect Magic",
+        "level": 1, "school": "divination", "actionType": "action", "target": "self",
+        "resources": {"itemsConsumed": [], "attributesConsumed": []},
+        "castingTime": "1 action", "range": "Self", "duration": "Concentration, up to 10 minutes",
+        "verbal": true, "somatic": true, "concentration": true, "ritual": true, "castingFlavour": "twinkly"})),extra)
+}
+/// A trigger listening for `event` at `timing`, with `fields` on top
+pub fn trigger(id: &str, event: &str, timing: &str, fields: &Value)->FlatProp{
+    let json = prop(id,"trigger",&json!({"event": event, "timing": timing}));
+    serde_json::from_value(with_fields(json,fields)).unwrap()
+}
+/// Add a copy of the fixture's damage multiplier, changed to multiply `damage_type` by `value` for properties with all of `include_tags`
+pub fn add_multiplier(character: &mut FlatCharacter, id: &str, damage_type: DamageType, value: f64, include_tags: &[&str]){
+    let mut prop = character.creature_properties.iter().find(|p|p.id=="multiplierId").unwrap().clone();
+    let PropType::DamageMultiplier{damage_types,value: old,include_tags: include,exclude_tags,..} = &mut prop.prop_type else {panic!("expected a damage multiplier")};
+    *damage_types = vec![damage_type];
+    *old = value;
+    **include = include_tags.iter().map(|t|t.to_string()).collect();
+    exclude_tags.clear();
+    prop.id = id.to_string();
+    character.creature_properties.push(prop);
+}
@@ -0,0 +1,32 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{DamageType, FlatCharacter, FlatProp, PropType};
+
+mod common;
+use common::add_multiplier;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn fixture_resistance(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let multiplier = &character.creature_properties.iter().find(|p|p.id=="multiplierId").unwrap().prop_type;
+    assert!(matches!(multiplier,PropType::DamageMultiplier{damage_types,..} if damage_types==&vec![DamageType::Fire]));
+    assert_eq!(character.damage_multiplier(&DamageType::Fire,&[]),0.5);
+    assert_eq!(character.damage_multiplier(&DamageType::Fire,&["magical"]),1.0);
+    assert_eq!(character.damage_multiplier(&DamageType::Cold,&[]),1.0);
+}
+#[test]
+fn combining_multipliers(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    add_multiplier(&mut character,"vulnerableId",DamageType::Fire,2.0,&[]);
+    assert_eq!(character.damage_multiplier(&DamageType::Fire,&[]),1.0);
+    assert_eq!(character.damage_multiplier(&DamageType::Fire,&["magical"]),2.0);
+    add_multiplier(&mut character,"immuneId",DamageType::Custom("shadow".to_string()),0.0,&["spell"]);
+    assert_eq!(character.damage_multiplier(&DamageType::from("shadow"),&["spell","magical"]),0.0);
+    assert_eq!(character.damage_multiplier(&DamageType::from("shadow"),&[]),1.0);
+    let immune: &mut FlatProp = character.creature_properties.iter_mut().find(|p|p.id=="immuneId").unwrap();
+    *immune.removed = true;
+    assert_eq!(character.damage_multiplier(&DamageType::from("shadow"),&["spell"]),1.0);
+    assert!(DamageType::TempHp.is_healing());
+    assert_eq!(serde_json::to_string(&DamageType::TempHp).unwrap(),"\"tempHP\"");
+}
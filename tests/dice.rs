@@ -0,0 +1,35 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{DiceRoll, FlatCharacter, ParseNode, PropVal, SeededRng};
+
+#[test]
+fn huge_dice_fail_instead_of_overflowing(){
+    let character = FlatCharacter::default();
+    let node = ParseNode::parse("3d18446744073709551615");
+    let outcome = node.roll(&character,&mut SeededRng::new(1));
+    assert_eq!(outcome.value,PropVal::default());
+    assert!(outcome.errors.iter().any(|e|e.message.contains("sides")),"{:?}",outcome.errors);
+    assert!(outcome.rolls.is_empty());
+    let outcome = ParseNode::parse("3d6").roll(&character,&mut |_sides|u64::MAX);
+    assert_eq!(outcome.value,PropVal::Number(i64::MAX));
+    assert_eq!(outcome.rolls[0].total,u64::MAX);
+}
+#[test]
+fn roll_nested(){
+    let num = |n|Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(n)});
+    let count = ParseNode::Operator{left: num(1),right: num(1),operator: "+".to_string(),fun: "add".to_string()};
+    let sides = ParseNode::Parenthesis{content: Box::new(ParseNode::Operator{left: num(2),right: num(3),
+        operator: "*".to_string(),fun: "multiply".to_string()})};
+    let node = ParseNode::Operator{left: Box::new(ParseNode::Roll{left: Box::new(count),right: Box::new(sides)}),
+        right: num(2),operator: "+".to_string(),fun: "add".to_string()};
+    let character = FlatCharacter::default();
+    let mut rolled = Vec::new();
+    let outcome = node.roll(&character,&mut |s|{rolled.push(s);s});
+    assert_eq!(outcome.value,PropVal::Number(14));
+    assert_eq!(outcome.rolls,vec![DiceRoll{number: 2,sides: 6,results: vec![6,6],total: 12}]);
+    assert_eq!(rolled,vec![6,6]);
+    let first = node.roll(&character,&mut SeededRng::new(7));
+    let second = node.roll(&character,&mut SeededRng::new(7));
+    assert_eq!(first,second);
+    assert!(first.errors.is_empty());
+    assert_eq!(node.evaluate(&character).1[0].message,"Rolls can't be evaluated without dice");
+}
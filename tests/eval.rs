@@ -0,0 +1,40 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{Calculation, FlatCharacter, ParseNode, PropVal};
+
+#[test]
+fn recompute_calculation(){
+    let tst = "{
+        \"calculation\": \"dne ? 1 : 0\",\"_key\": \"baseValue\",\"type\": \"_calculation\",\"hash\": 5843567941511658,
+        \"parseNode\": {
+            \"parseType\": \"if\",\"condition\": {\"parseType\": \"symbol\",\"name\": \"dne\"},
+            \"consequent\": {\"parseType\": \"constant\",\"valueType\": \"number\",\"value\": 1},
+            \"alternative\": {\"parseType\": \"constant\",\"valueType\": \"number\",\"value\": 0}},
+        \"parseError\": null,\"errors\": [{\"type\": \"info\",\"message\": \"dne not found, set to 0\"}],\"value\": 0}";
+    let deser: Calculation = serde_json::from_str(tst).unwrap();
+    let mut recomputed = deser.clone();
+    recomputed.recompute(&FlatCharacter::default());
+    assert_eq!(recomputed, deser);
+}
+#[test]
+fn evaluate_variables(){
+    let vars = "{
+        \"_id\": \"vars\",
+        \"strength\": {\"_id\": \"a\",\"variableName\": \"strength\",\"name\": \"Strength\",\"order\": 1,
+            \"type\": \"attribute\",\"total\": 14,\"value\": 14,\"modifier\": 2},
+        \"proficiencyBonus\": {\"_id\": \"b\",\"variableName\": \"proficiencyBonus\",\"order\": 2,
+            \"type\": \"attribute\",\"total\": 3,\"value\": 3}
+    }";
+    let character = FlatCharacter{creature_variables: vec![serde_json::from_str(vars).unwrap()],..Default::default()};
+    let modifier = ParseNode::Accessor{path: vec!["modifier".to_string()],name: "strength".to_string()};
+    let prof = ParseNode::Symbol{name: "proficiencyBonus".to_string()};
+    let two = ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(2)};
+    let node = ParseNode::Operator{left: Box::new(modifier),
+        right: Box::new(ParseNode::Operator{left: Box::new(prof),right: Box::new(two),operator: "*".to_string(),fun: "multiply".to_string()}),
+        operator: "+".to_string(),fun: "add".to_string()};
+    assert_eq!(node.evaluate(&character),(PropVal::Number(8),vec![]));
+    let half = ParseNode::Call{function_name: "floor".to_string(),args: vec![Box::new(ParseNode::Operator{
+        left: Box::new(ParseNode::Symbol{name: "strength".to_string()}),
+        right: Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(4)}),
+        operator: "/".to_string(),fun: "multiply".to_string()})]};
+    assert_eq!(half.evaluate(&character),(PropVal::Number(3),vec![]));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{Error, FlatCharacter, PropType};
+
+#[test]
+fn from_json_errors(){
+    let prop = |body: &str|format!("{{\"creatures\": [],\"creatureVariables\": [],\"creatureProperties\": [
+        {{\"_id\": \"ok\",\"type\": \"folder\",\"tags\": [],\"order\": 0,\"parent\": {{\"id\": \"c\",\"collection\": \"creatures\"}},
+            \"ancestors\": [{{\"id\": \"c\",\"collection\": \"creatures\"}}]}},
+        {{\"_id\": \"bad\",\"tags\": [],\"parent\": {{\"id\": \"c\",\"collection\": \"creatures\"}},
+            \"ancestors\": [{{\"id\": \"c\",\"collection\": \"creatures\"}}],{body}}}]}}");
+    let err = FlatCharacter::from_json(&prop("\"order\": 1,\"type\": \"spaceship\"")).unwrap_err();
+    assert!(matches!(&err,Error::UnknownPropType{typ,..} if typ=="spaceship"),"{err}");
+    assert_eq!((err.id(),err.path()),(Some("bad"),Some("creatureProperties[1].type")));
+    // a bad value for any other enum isn't an unknown property type. Fields of the property type are blamed on the property
+    for (body,path) in [("\"order\": 1,\"type\": \"attribute\",\"variableName\": \"x\",\"attributeType\": \"bogus\"","creatureProperties[1]"),
+        ("\"order\": 1,\"type\": \"attribute\",\"variableName\": \"x\",\"attributeType\": \"ability\",\"baseValue\": {\"calculation\": 5}","creatureProperties[1]"),
+        ("\"order\": 1,\"type\": \"folder\",\"libraryTags\": [\"a\",5]","creatureProperties[1].libraryTags[1]")]{
+        let err = FlatCharacter::from_json(&prop(body)).unwrap_err();
+        assert!(matches!(&err,Error::InvalidField{..}),"{err}");
+        assert_eq!(err.path(),Some(path),"{err}");
+    }
+    let err = FlatCharacter::from_json(&prop("\"type\": \"folder\"")).unwrap_err();
+    assert!(matches!(&err,Error::MissingField{field,..} if field=="order"),"{err}");
+    assert_eq!(err.path(),Some("creatureProperties[1]"));
+    let err = FlatCharacter::from_json(&prop("\"order\": 1,\"type\": \"attribute\",\"variableName\": \"x\",\"attributeType\": \"ability\",\"baseValue\": {}")).unwrap_err();
+    assert!(matches!(&err,Error::MissingField{field,..} if field=="calculation"),"{err}");
+    assert_eq!(err.path(),Some("creatureProperties[1]"));
+    assert!(matches!(FlatCharacter::from_json("{\"creatures\": ["),Err(Error::Json(_))));
+    assert!(FlatCharacter::from_json(&prop("\"order\": 1,\"type\": \"folder\"")).is_ok());
+}
+#[test]
+fn lenient_import(){
+    let json = "{\"creatures\": [],\"creatureVariables\": [],\"creatureProperties\": [
+        {\"_id\": \"ok\",\"type\": \"folder\",\"tags\": [],\"order\": 0,\"parent\": {\"id\": \"c\",\"collection\": \"creatures\"},
+            \"ancestors\": [{\"id\": \"c\",\"collection\": \"creatures\"}]},
+        {\"_id\": \"new\",\"type\": \"spaceship\",\"warp\": 9,\"tags\": [\"fast\"],\"order\": 1,\"parent\": {\"id\": \"ok\",\"collection\": \"creatureProperties\"},
+            \"ancestors\": [{\"id\": \"c\",\"collection\": \"creatures\"},{\"id\": \"ok\",\"collection\": \"creatureProperties\"}]},
+        {\"_id\": \"broken\",\"type\": \"folder\",\"tags\": [],\"parent\": {\"id\": \"c\",\"collection\": \"creatures\"},
+            \"ancestors\": [{\"id\": \"c\",\"collection\": \"creatures\"}]}]}";
+    assert!(FlatCharacter::from_json(json).is_err());
+    let (character,warnings) = FlatCharacter::from_json_lenient(json).unwrap();
+    assert_eq!(character.creature_properties.len(),3);
+    assert_eq!(warnings.iter().map(Error::id).collect::<Vec<_>>(),vec![Some("new"),Some("broken")]);
+    let unknown = &character.creature_properties[1];
+    assert_eq!((unknown.id.as_str(),unknown.order,unknown.tags.clone()),("new",1,vec!["fast".to_string()]));
+    assert!(matches!(unknown.prop_type,PropType::Unknown{..}));
+    let reserialized = serde_json::to_value(unknown).unwrap();
+    assert_eq!(reserialized["type"],"spaceship");
+    assert_eq!(reserialized["warp"],9);
+    assert_eq!(reserialized["order"],1);
+}
@@ -0,0 +1,37 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::FlatCharacter;
+
+mod common;
+use common::ids;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn lookups(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let index = character.index();
+    assert_eq!(index.len(),character.creature_properties.len());
+    assert_eq!(index.by_id("noteId").map(|p|p.order),Some(2));
+    assert!(index.by_id("gone").is_none());
+    assert_eq!(ids(index.by_variable_name("strength")),vec!["strengthId"]);
+    assert_eq!(ids(index.by_variable_name("raging")),vec!["toggleId"]);
+    assert!(index.by_variable_name("dne").is_empty());
+    assert_eq!(ids(index.by_tag("weapon")),vec!["actionId"]);
+    assert_eq!(ids(index.by_tag("gear")),vec!["itemId"]);
+    assert_eq!(ids(index.by_type("damageMultiplier")),vec!["multiplierId"]);
+    assert_eq!(ids(index.by_type("folder")),vec!["featuresFolderId"]);
+    assert!(index.by_type("spell").is_empty());
+}
+#[test]
+fn shared_names_keep_property_order(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let mut copy = character.creature_properties[0].clone();
+    copy.id = "strengthCopyId".to_string();
+    copy.tags = vec!["extra".to_string()];
+    *copy.library_tags = vec!["extra".to_string()];
+    character.creature_properties.push(copy);
+    let index = character.index();
+    assert_eq!(ids(index.by_variable_name("strength")),vec!["strengthId","strengthCopyId"]);
+    assert_eq!(ids(index.by_tag("extra")),vec!["strengthCopyId"]);
+    assert_eq!(ids(index.by_type("attribute")),vec!["strengthId","strengthCopyId"]);
+}
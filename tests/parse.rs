@@ -0,0 +1,90 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{Calculation, ParseNode, PropVal};
+
+fn symbol(name: &str)->ParseNode{
+    ParseNode::Symbol{name: name.to_string()}
+}
+#[test]
+fn prefixed_names(){
+    assert_eq!(ParseNode::parse("#foo"),symbol("#foo"));
+    assert_eq!(ParseNode::parse("$x + 1"),ParseNode::Operator{left: Box::new(symbol("$x")),
+        right: Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(1)}),
+        operator: "+".to_string(),fun: "add".to_string()});
+    let accessor = ParseNode::parse("#attribute.total");
+    assert_eq!(accessor,ParseNode::Accessor{path: vec!["total".to_string()],name: "#attribute".to_string()});
+    assert_eq!(ParseNode::parse(&accessor.to_string()),accessor);
+    assert_eq!(ParseNode::parse("$"),symbol("$"));
+}
+#[test]
+fn print_parse_node(){
+    let num = |n|Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(n)});
+    let op = |left,operator: &str,right|Box::new(ParseNode::Operator{left,right,operator: operator.to_string(),fun: String::new()});
+    let modifier = Box::new(ParseNode::Accessor{path: vec!["modifier".to_string()],name: "strength".to_string()});
+    let cond = ParseNode::If{condition: Box::new(ParseNode::Symbol{name: "dne".to_string()}),consequent: num(1),alternative: num(0)};
+    assert_eq!(cond.to_string(),"dne ? 1 : 0");
+    assert_eq!(op(modifier.clone(),"+",op(num(2),"*",num(3))).to_string(),"strength.modifier + 2 * 3");
+    assert_eq!(op(num(1),"-",op(num(2),"-",num(3))).to_string(),"1 - (2 - 3)");
+    assert_eq!(op(op(num(2),"^",num(3)),"^",num(2)).to_string(),"(2 ^ 3) ^ 2");
+    let roll = ParseNode::Roll{left: num(1),right: num(20)};
+    let call = ParseNode::Call{function_name: "max".to_string(),args: vec![Box::new(roll),modifier]};
+    assert_eq!(op(Box::new(call),"+",Box::new(ParseNode::Parenthesis{content: Box::new(cond)})).to_string(),
+        "max(1d20, strength.modifier) + (dne ? 1 : 0)");
+    let sides = Box::new(ParseNode::Symbol{name: "sides".to_string()});
+    assert_eq!(ParseNode::Roll{left: op(num(1),"+",num(1)),right: sides}.to_string(),"(1 + 1)d sides");
+    let mut calc = Calculation::default();
+    calc.set_parse_node(ParseNode::Not{right: op(num(1),"==",num(2))});
+    assert_eq!(calc.calculation,"!(1 == 2)");
+    // error nodes have no text of their own, so the text they were parsed from is kept
+    let mut calc = Calculation::from_formula("baseValue","1 +");
+    let node = calc.parse_node.clone();
+    assert!(matches!(node,ParseNode::Error{..}));
+    calc.set_parse_node(node.clone());
+    assert_eq!(calc.calculation,"1 +");
+    assert_eq!(ParseNode::parse(&calc.calculation),node);
+}
+#[test]
+fn parse_formula(){
+    let deser: ParseNode = serde_json::from_str("{
+        \"parseType\": \"if\",\"condition\": {\"parseType\": \"symbol\",\"name\": \"dne\"},
+        \"consequent\": {\"parseType\": \"constant\",\"valueType\": \"number\",\"value\": 1},
+        \"alternative\": {\"parseType\": \"constant\",\"valueType\": \"number\",\"value\": 0}}").unwrap();
+    assert_eq!(ParseNode::parse("dne ? 1 : 0"),deser);
+    let deser: ParseNode = serde_json::from_str("{
+        \"parseType\": \"operator\",\"left\": {\"parseType\": \"symbol\",\"name\": \"carryingCapacity\"},
+        \"right\": {\"parseType\": \"constant\",\"valueType\": \"number\",\"value\": 2},
+        \"operator\": \"*\",\"fn\": \"multiply\"}").unwrap();
+    assert_eq!(ParseNode::parse("carryingCapacity*2"),deser);
+    let node = ParseNode::parse("strength.modifier + proficiencyBonus * 2");
+    let ParseNode::Operator{left,..} = &node else {panic!("expected an operator, got {node:?}")};
+    assert_eq!(**left,ParseNode::Accessor{path: vec!["modifier".to_string()],name: "strength".to_string()});
+    for formula in ["1d20 + (dne ? 1 : 0)","d20","2d6 + 3 >= 10 && !toggle","max(1, 2, [1, 2][1]) - -3",
+        "a ? b : c ? x : y","2 ^ 3 ^ 2","tableLookup([0, 300, 900], xp)","\"some \\\"text\\\"\" + 'more'","x d sides"]{
+        let parsed = ParseNode::parse(formula);
+        assert!(!matches!(parsed,ParseNode::Error{..}),"{formula} failed to parse: {parsed:?}");
+        assert_eq!(ParseNode::parse(&parsed.to_string()),parsed,"{formula} did not round trip");
+    }
+    assert_eq!(ParseNode::parse("d20"),ParseNode::Roll{
+        left: Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(1)}),
+        right: Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(20)})});
+    let roll = |left,right|Box::new(ParseNode::Roll{left,right});
+    let num = |n|Box::new(ParseNode::Constant{value_type: "number".to_string(),value: PropVal::Number(n)});
+    let chained = ParseNode::parse("1d6d4");
+    assert_eq!(chained,*roll(roll(num(1),num(6)),num(4)));
+    assert_eq!(ParseNode::parse(&chained.to_string()),chained);
+    assert_eq!(ParseNode::parse("dd"),symbol("dd"));
+    let ParseNode::Error{error} = ParseNode::parse("1 + (2") else {panic!("expected an error")};
+    assert_eq!(error.message,"Unexpected end of input");
+    assert_eq!(Calculation::from_formula("baseValue","1 +").errors.len(),1);
+}
+#[test]
+fn nesting_limit(){
+    let nested = |open: &str,depth,close: &str|format!("{}1{}",open.repeat(depth),close.repeat(depth));
+    for formula in [nested("(",300,")"),nested("-",300,""),nested("[",300,"]"),nested("2 ^ ",300,"")]{
+        let ParseNode::Error{error} = ParseNode::parse(&formula) else {panic!("expected an error for {formula}")};
+        assert_eq!(error.message,"formula nested too deeply");
+    }
+    for formula in [nested("(",20,")"),nested("-",20,""),nested("2 ^ ",20,"")]{
+        let parsed = ParseNode::parse(&formula);
+        assert!(!matches!(parsed,ParseNode::Error{..}),"{formula} failed to parse: {parsed:?}");
+    }
+}
@@ -0,0 +1,105 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{FlatCharacter, FlatProp, TreeCharacter, TreeProp};
+use serde_json::Value;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+/// The path to the first place two json values differ
+fn first_difference(path: &str, actual: &Value, expected: &Value)->Option<String>{
+    match (actual,expected){
+        (Value::Object(a),Value::Object(e))=>a.keys().chain(e.keys()).find_map(|key|match (a.get(key),e.get(key)){
+            (Some(a),Some(e))=>first_difference(&format!("{path}.{key}"),a,e),
+            (a,e)=>Some(format!("{path}.{key}: {a:?} != {e:?}"))
+        }),
+        (Value::Array(a),Value::Array(e)) if a.len()==e.len()=>a.iter().zip(e).enumerate()
+            .find_map(|(i,(a,e))|first_difference(&format!("{path}[{i}]"),a,e)),
+        (a,e)=>(a!=e).then(||format!("{path}: {a} != {e}"))
+    }
+}
+/// Checks two json documents hold exactly the same values, so `1` and `1.0` or a `null` and a missing field are different
+fn assert_same_json(actual: &str, expected: &str){
+    let actual: Value = serde_json::from_str(actual).unwrap();
+    let expected: Value = serde_json::from_str(expected).unwrap();
+    if let Some(difference) = first_difference("",&actual,&expected){
+        panic!("json differs at {difference}");
+    }
+    assert_eq!(actual,expected);
+}
+#[test]
+fn flat_round_trip(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    assert_same_json(&serde_json::to_string(&character).unwrap(),CHARACTER);
+}
+#[test]
+fn absent_fields_stay_absent(){
+    // the fixture writes out most defaults, dicecloud often leaves them out instead. Only required fields are kept
+    let mut sparse: Value = serde_json::from_str(CHARACTER).unwrap();
+    for prop in sparse["creatureProperties"].as_array_mut().unwrap(){
+        prop.as_object_mut().unwrap().retain(|key,value|matches!(key.as_str(),"tags"|"equipped") || !matches!(value,Value::Null|Value::Bool(false))
+            && value.as_array().is_none_or(|a|!a.is_empty()));
+    }
+    let sparse = sparse.to_string();
+    assert!(!sparse.contains("\"removed\":false"));
+    let character = FlatCharacter::from_json(&sparse).unwrap();
+    assert_same_json(&character.to_json().unwrap(),&sparse);
+}
+#[test]
+fn whole_numbers_stay_whole(){
+    let json = CHARACTER.replacen("\"weight\": 10.5","\"weight\": 2",1);
+    let character = FlatCharacter::from_json(&json).unwrap();
+    assert_same_json(&character.to_json().unwrap(),&json);
+}
+#[test]
+fn each_property_round_trips(){
+    let raw: Value = serde_json::from_str(CHARACTER).unwrap();
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    for (prop,raw) in character.creature_properties.iter().zip(raw["creatureProperties"].as_array().unwrap()){
+        assert_same_json(&serde_json::to_string(prop).unwrap(),&raw.to_string());
+    }
+}
+#[test]
+fn unknown_fields_are_kept(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    assert_eq!(character.extra.get("exportedAt").and_then(Value::as_str),Some("2024-01-01T00:00:00.000Z"));
+    assert!(character.creatures[0].extra.get("damageMultipliers").is_some());
+    assert_eq!(character.creatures[0].settings.extra.get("hideCalculationErrors"),Some(&Value::Bool(true)));
+}
+#[test]
+fn lenient_round_trip(){
+    let (character,warnings) = FlatCharacter::from_json_lenient(CHARACTER).unwrap();
+    assert!(warnings.is_empty());
+    assert_same_json(&serde_json::to_string(&character).unwrap(),CHARACTER);
+}
+#[test]
+fn io_helpers_round_trip(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    assert_same_json(&character.to_json_pretty().unwrap(),CHARACTER);
+    let bytes = character.to_json_bytes().unwrap();
+    assert_eq!(FlatCharacter::from_json_bytes(&bytes).unwrap(),character);
+    let mut written = Vec::new();
+    character.to_json_writer_pretty(&mut written).unwrap();
+    assert_eq!(FlatCharacter::from_json_reader(written.as_slice()).unwrap(),character);
+
+    let prop = &character.creature_properties[1];
+    assert_eq!(&FlatProp::from_json(&prop.to_json().unwrap()).unwrap(),prop);
+    let tree = TreeCharacter::build_tree(character.clone());
+    assert_eq!(TreeCharacter::from_json_bytes(&tree.to_json_bytes_pretty().unwrap()).unwrap(),tree);
+    let root = tree.roots().next().unwrap();
+    let mut written = Vec::new();
+    root.to_json_writer(&mut written).unwrap();
+    assert_eq!(&TreeProp::from_json_reader(written.as_slice()).unwrap(),root);
+}
+#[test]
+fn io_helpers_locate_errors(){
+    let broken = CHARACTER.replacen("\"type\": \"toggle\"","\"type\": \"spaceship\"",1);
+    let err = FlatCharacter::from_json_reader(broken.as_bytes()).unwrap_err();
+    assert_eq!(err.id(),Some("toggleId"));
+    assert!(FlatCharacter::from_json_bytes(b"{\"creatures\": [\xff]}").is_err());
+}
+#[test]
+fn tree_round_trip(){
+    let character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let mut flat = FlatCharacter::from_tree_char(TreeCharacter::build_tree(character));
+    flat.creature_properties.sort_by_key(|p|p.order);
+    assert_same_json(&flat.to_json().unwrap(),CHARACTER);
+}
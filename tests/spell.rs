@@ -0,0 +1,43 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{CastingTime, Components, FlatProp, PropType, SpellDuration, SpellRange, SpellSchool};
+use serde_json::json;
+
+mod common;
+use common::spell;
+
+#[test]
+fn typed_fields_round_trip(){
+    let json = spell(&json!({"material": "a pinch of dust", "range": "60 ft."}));
+    let prop: FlatProp = serde_json::from_value(json.clone()).unwrap();
+    let PropType::Spell{components,school,ritual,concentration,..} = &prop.prop_type else {panic!("expected a spell")};
+    assert_eq!(components,&Components{verbal: true.into(), somatic: true.into(), material: Some("a pinch of dust".to_string()).into()});
+    assert_eq!(components.to_string(),"V, S, M (a pinch of dust)");
+    assert_eq!(school,&SpellSchool::Divination);
+    assert!(**ritual && **concentration);
+    assert_eq!(prop.prop_type.spell_level(),Some(1));
+    assert_eq!(prop.prop_type.spell_casting_time(),Some(CastingTime::Action));
+    assert_eq!(prop.prop_type.spell_range(),Some(SpellRange::Feet(60)));
+    assert_eq!(prop.prop_type.spell_duration(),Some(SpellDuration::Minutes(10)));
+    let written = serde_json::to_value(&prop).unwrap();
+    for key in ["verbal","somatic","material","school","castingFlavour","range"]{
+        assert_eq!(written[key],json[key],"{key} changed");
+    }
+    assert_eq!(serde_json::from_value::<FlatProp>(written).unwrap(),prop);
+}
+#[test]
+fn parsing(){
+    let times = ["1 bonus action","1 reaction, which you take when you are hit","10 minutes","1 hour","when the moon is full"].map(CastingTime::parse);
+    assert_eq!(times,[CastingTime::BonusAction,CastingTime::Reaction,CastingTime::Minutes(10),CastingTime::Hours(1),
+        CastingTime::Raw("when the moon is full".to_string())]);
+    let ranges = ["Touch","Self (15-foot cone)","120 feet","1 mile","far away"].map(SpellRange::parse);
+    assert_eq!(ranges,[SpellRange::Touch,SpellRange::Self_,SpellRange::Feet(120),SpellRange::Miles(1),SpellRange::Raw("far away".to_string())]);
+    let durations = ["Instantaneous","1 round","Concentration, up to 1 hour","Until dispelled or triggered","8 hours","a while"].map(SpellDuration::parse);
+    assert_eq!(durations,[SpellDuration::Instantaneous,SpellDuration::Rounds(1),SpellDuration::Hours(1),SpellDuration::UntilDispelled,
+        SpellDuration::Hours(8),SpellDuration::Raw("a while".to_string())]);
+    assert_eq!(SpellDuration::Rounds(1).to_string(),"1 round");
+    assert_eq!(CastingTime::Minutes(10).to_string(),"10 minutes");
+    let prop: FlatProp = serde_json::from_value(spell(&json!({"school": "chronurgy", "castingTime": "1 turn"}))).unwrap();
+    assert_eq!(prop.prop_type.spell_school(),Some(&SpellSchool::Other("chronurgy".to_string())));
+    assert_eq!(prop.prop_type.spell_casting_time(),Some(CastingTime::Raw("1 turn".to_string())));
+    assert!(prop.prop_type.spell_components().is_some_and(|c|!c.is_empty()));
+}
@@ -0,0 +1,47 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{CharacterPart, CharacterReader, Error, FlatCharacter, PropType};
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn stream_matches_full_read(){
+    let full = FlatCharacter::from_json(CHARACTER).unwrap();
+    let streamed: FlatCharacter = CharacterReader::new(CHARACTER.as_bytes()).collect::<Result<_,_>>().unwrap();
+    assert_eq!(streamed,full);
+}
+#[test]
+fn stream_yields_in_document_order(){
+    let parts: Vec<CharacterPart> = CharacterReader::new(CHARACTER.as_bytes()).collect::<Result<_,_>>().unwrap();
+    assert!(matches!(parts.first(),Some(CharacterPart::Creature(c)) if c.id=="creatureId1"));
+    assert!(matches!(parts.last(),Some(CharacterPart::Other(key,_)) if key=="exportedAt"));
+    assert_eq!(parts.iter().filter(|p|matches!(p,CharacterPart::Property(_))).count(),8);
+}
+#[test]
+fn stream_filters_properties(){
+    let toggles: Vec<String> = CharacterReader::new(CHARACTER.as_bytes()).properties()
+        .filter_map(Result::ok)
+        .filter(|p|matches!(p.prop_type,PropType::Toggle{..}))
+        .map(|p|p.id)
+        .collect();
+    assert_eq!(toggles,vec!["toggleId".to_string()]);
+}
+#[test]
+fn stream_carries_on_after_a_bad_entry(){
+    let broken = CHARACTER.replacen("\"type\": \"toggle\"","\"type\": \"spaceship\"",1);
+    let parts: Vec<Result<CharacterPart,Error>> = CharacterReader::new(broken.as_bytes()).collect();
+    let errors: Vec<&Error> = parts.iter().filter_map(|p|p.as_ref().err()).collect();
+    assert_eq!(errors.len(),1);
+    assert_eq!(errors[0].id(),Some("toggleId"));
+    assert_eq!(errors[0].path(),Some("creatureProperties[6].type"));
+    assert!(matches!(parts.last(),Some(Ok(CharacterPart::Other(..)))));
+}
+#[test]
+fn stream_stops_on_broken_json(){
+    let parts: Vec<Result<CharacterPart,Error>> = CharacterReader::new(&b"{\"creatures\": [} []]}"[..]).collect();
+    assert_eq!(parts.len(),1);
+    assert!(parts[0].is_err());
+    let truncated = &CHARACTER[..CHARACTER.len()/2];
+    let parts: Vec<Result<CharacterPart,Error>> = CharacterReader::new(truncated.as_bytes()).collect();
+    assert!(parts.last().unwrap().is_err());
+    assert!(parts.iter().rev().skip(1).all(Result::is_ok));
+}
@@ -0,0 +1,265 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{Error, FlatCharacter, Identifier, OrphanHandling, TreeCharacter};
+
+mod common;
+use common::ids;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn tree_nests_under_parents(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    assert_eq!(ids(tree.roots()),vec!["strengthId","featuresFolderId","actionId","itemId","toggleId","multiplierId"]);
+    let folder = tree.roots().nth(1).unwrap();
+    assert_eq!(ids(folder.children()),vec!["noteId"]);
+    let action = tree.roots().nth(2).unwrap();
+    assert_eq!(ids(action.children()),vec!["branchId"]);
+}
+#[test]
+fn flatten_is_pre_order(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    character.creature_properties.reverse();
+    let flat = FlatCharacter::from_tree_char(TreeCharacter::build_tree(character));
+    let order: Vec<&str> = flat.creature_properties.iter().map(|p|p.id.as_str()).collect();
+    assert_eq!(order,vec!["strengthId","featuresFolderId","noteId","actionId","branchId","itemId","toggleId","multiplierId"]);
+}
+#[test]
+fn children_follow_order(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    for root in tree.roots_mut(){
+        root.order = 100-root.order;
+    }
+    tree.sort();
+    assert_eq!(ids(tree.roots()),vec!["multiplierId","toggleId","itemId","actionId","featuresFolderId","strengthId"]);
+    let mut roots = tree.take_roots();
+    let mut folder = roots.remove(4);
+    let note = folder.take_children().remove(0);
+    let mut first = note.clone();
+    first.id = "first".to_string();
+    first.order = 0;
+    folder.add_child(note);
+    folder.add_child(first.clone());
+    assert_eq!(ids(folder.children()),vec!["first","noteId"]);
+    // placeholders made on the way down go in by order too
+    folder.recurse_insert(["placeholderId".to_string()],first);
+    assert_eq!(ids(folder.children()),vec!["first","placeholderId","noteId"]);
+}
+/// The test character with the features folder removed, orphaning the note under it
+fn without_folder()->FlatCharacter{
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    character.creature_properties.retain(|p|p.id!="featuresFolderId");
+    character
+}
+#[test]
+fn roots_sorting_after_children_are_kept(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let action = character.creature_properties.iter_mut().find(|p|p.id=="actionId").unwrap();
+    action.order = 50;
+    let tree = TreeCharacter::try_build_tree(character).unwrap();
+    let action = tree.roots().last().unwrap();
+    assert_eq!(action.id,"actionId");
+    assert_eq!(ids(action.children()),vec!["branchId"]);
+}
+#[test]
+fn orphans_get_placeholders(){
+    let (tree,report) = TreeCharacter::build_tree_with(without_folder(),OrphanHandling::Placeholders);
+    assert!(!report.is_clean());
+    assert_eq!(report.placeholders,vec!["featuresFolderId".to_string()]);
+    assert!(matches!(&report.orphans[..],[Error::BrokenLinkage{id,missing}] if id=="noteId" && missing=="featuresFolderId"));
+    let placeholder = tree.roots().find(|p|p.id=="featuresFolderId").unwrap();
+    assert_eq!(ids(placeholder.children()),vec!["noteId"]);
+    assert_eq!(placeholder.parent.id,"creatureId1");
+    let flat = FlatCharacter::from_tree_char(tree);
+    assert_eq!(flat.creature_properties.len(),8);
+    assert!(matches!(TreeCharacter::try_build_tree(without_folder()),Err(Error::BrokenLinkage{..})));
+}
+#[test]
+fn orphans_under_synthetic_root(){
+    let mut character = without_folder();
+    // give the orphaned note a child of its own, which should stay under it
+    let mut child = character.creature_properties.iter().find(|p|p.id=="itemId").unwrap().clone();
+    child.id = "underNote".to_string();
+    let note = character.creature_properties.iter().find(|p|p.id=="noteId").unwrap();
+    child.ancestors = note.ancestors.clone();
+    child.ancestors.push(Identifier::property("noteId"));
+    character.creature_properties.push(child);
+    let (tree,report) = TreeCharacter::build_tree_with(character,OrphanHandling::SyntheticRoot);
+    assert!(report.placeholders.is_empty());
+    assert_eq!(report.orphans.len(),1);
+    let synthetic = tree.roots().last().unwrap();
+    assert_eq!(Some(&synthetic.id),report.orphan_root.as_ref());
+    assert_eq!(synthetic.id.len(),17);
+    assert!(synthetic.id.chars().all(|c|"23456789ABCDEFGHJKLMNPQRSTWXYZabcdefghijkmnopqrstuvwxyz".contains(c)),"{}",synthetic.id);
+    assert_eq!(ids(synthetic.children()),vec!["noteId"]);
+    // the orphans are relinked to the new folder, all the way down
+    let note = synthetic.children().next().unwrap();
+    let root = Identifier::property(synthetic.id.clone());
+    assert_eq!((&note.parent,&note.ancestors),(&root,&vec![Identifier::creature("creatureId1"),root.clone()]));
+    let under = note.children().next().unwrap();
+    assert_eq!(under.id,"underNote");
+    assert_eq!(under.ancestors,vec![Identifier::creature("creatureId1"),root.clone(),Identifier::property("noteId")]);
+    let flat = FlatCharacter::from_tree_char(tree);
+    assert!(TreeCharacter::try_build_tree(flat).is_ok());
+}
+#[test]
+fn lookup_by_id(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    assert_eq!(tree.get("noteId").unwrap().id,"noteId");
+    assert_eq!(tree.get("strengthId").unwrap().order,0);
+    assert!(tree.get("dne").is_none());
+    assert_eq!(tree.parent_of("noteId").unwrap().id,"featuresFolderId");
+    assert!(tree.parent_of("featuresFolderId").is_none());
+    assert_eq!(ids(tree.path_to("branchId").unwrap().into_iter()),vec!["actionId","branchId"]);
+    assert!(tree.contains("itemId"));
+}
+/// Whether every property in the tree is where the index says it is
+fn every_prop_found(tree: &TreeCharacter)->bool{
+    tree.pre_order().all(|v|tree.get(&v.prop.id).is_some_and(|p|std::ptr::eq(p,v.prop))
+        && tree.path_to(&v.prop.id).is_some_and(|path|path.len()==v.depth+1))
+}
+#[test]
+fn edits_keep_index_in_sync(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    *tree.get_mut("noteId").unwrap().inactive = true;
+    assert!(*tree.get("noteId").unwrap().inactive);
+
+    let action = tree.remove("actionId").unwrap();
+    assert_eq!(ids(action.children()),vec!["branchId"]);
+    assert!(!tree.contains("actionId"));
+    assert!(!tree.contains("branchId"));
+    assert!(tree.remove("branchId").is_none());
+
+    // the roots after the action moved up, and are still found where they are now
+    assert!(every_prop_found(&tree));
+    tree.add_root(action);
+    assert_eq!(tree.parent_of("branchId").unwrap().id,"actionId");
+    assert!(every_prop_found(&tree));
+
+    // children added behind the tree's back are found once it is reindexed
+    let mut extra = tree.get("itemId").unwrap().clone();
+    extra.id = "hidden".to_string();
+    tree.get_mut("noteId").unwrap().add_child(extra);
+    assert!(!tree.contains("hidden"));
+    tree.reindex();
+    assert_eq!(ids(tree.path_to("hidden").unwrap().into_iter()),vec!["featuresFolderId","noteId","hidden"]);
+    assert_eq!(tree.remove("hidden").unwrap().id,"hidden");
+    assert_eq!(tree.get("noteId").unwrap().children().count(),0);
+}
+#[test]
+fn index_survives_json(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    let read = TreeCharacter::from_json(&tree.to_json().unwrap()).unwrap();
+    assert_eq!(read,tree);
+    assert_eq!(read.parent_of("branchId").unwrap().id,"actionId");
+}
+/// Write siblings the way older versions did, as a map from id to property
+fn as_maps(props: &serde_json::Value)->serde_json::Value{
+    props.as_array().unwrap().iter().rev().map(|prop|{
+        let mut prop = prop.clone();
+        if let Some(children) = prop.as_object_mut().unwrap().remove("children"){
+            prop["childMap"] = as_maps(&children);
+        }
+        (prop["_id"].as_str().unwrap().to_string(),prop)
+    }).collect::<serde_json::Map<_,_>>().into()
+}
+#[test]
+fn reads_older_tree_json(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    let mut json = serde_json::to_value(&tree).unwrap();
+    let props = json.as_object_mut().unwrap().remove("creaturePropertiesTree").unwrap();
+    json["creaturePropertiesTmap"] = as_maps(&props);
+    assert_eq!(serde_json::from_value::<TreeCharacter>(json).unwrap(),tree);
+}
+#[test]
+fn traversal_orders(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    let pre: Vec<(&str,usize)> = tree.pre_order().map(|v|(v.prop.id.as_str(),v.depth)).collect();
+    assert_eq!(pre,vec![("strengthId",0),("featuresFolderId",0),("noteId",1),("actionId",0),("branchId",1),
+        ("itemId",0),("toggleId",0),("multiplierId",0)]);
+    let post: Vec<&str> = tree.post_order().map(|v|v.prop.id.as_str()).collect();
+    assert_eq!(post,vec!["strengthId","noteId","featuresFolderId","branchId","actionId","itemId","toggleId","multiplierId"]);
+    let bfs: Vec<&str> = tree.breadth_first().map(|v|v.prop.id.as_str()).collect();
+    assert_eq!(bfs,vec!["strengthId","featuresFolderId","actionId","itemId","toggleId","multiplierId","noteId","branchId"]);
+    let branch = tree.pre_order().find(|v|v.prop.id=="branchId").unwrap();
+    assert_eq!(branch.parent().unwrap().id,"actionId");
+    assert_eq!(ids(branch.ancestors.into_iter()),vec!["actionId"]);
+}
+#[test]
+fn subtree_traversal(){
+    let tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    let action = tree.get("actionId").unwrap();
+    let pre: Vec<(&str,usize)> = action.pre_order().map(|v|(v.prop.id.as_str(),v.depth)).collect();
+    assert_eq!(pre,vec![("actionId",0),("branchId",1)]);
+    assert_eq!(action.post_order().last().unwrap().prop.id,"actionId");
+    assert_eq!(action.breadth_first().count(),2);
+}
+#[test]
+fn mutable_visitor(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    let mut seen = Vec::new();
+    tree.visit_mut(|prop,depth,above|{
+        seen.push((prop.id.clone(),depth,above.to_vec()));
+        if depth>0{
+            *prop.inactive = true;
+        }
+        // dropping the branch under the action means it is never visited
+        if prop.id=="actionId"{
+            prop.take_children();
+        }
+    });
+    assert_eq!(seen.len(),7);
+    assert!(seen.contains(&("noteId".to_string(),1,vec!["featuresFolderId".to_string()])));
+    assert!(*tree.get("noteId").unwrap().inactive);
+    assert!(!tree.contains("branchId"));
+}
+#[test]
+fn move_node_relinks_subtree(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    // move the action, with its branch, into the features folder ahead of the note
+    tree.move_node("actionId",Some("featuresFolderId"),0).unwrap();
+    assert_eq!(ids(tree.get("featuresFolderId").unwrap().children()),vec!["actionId","noteId"]);
+    assert_eq!(tree.parent_of("branchId").unwrap().id,"actionId");
+    let branch = tree.get("branchId").unwrap();
+    let chain: Vec<&str> = branch.ancestors.iter().map(|a|a.id.as_str()).collect();
+    assert_eq!(chain,vec!["creatureId1","featuresFolderId","actionId"]);
+    assert_eq!(branch.parent.id,"actionId");
+    assert_eq!(tree.get("actionId").unwrap().parent.id,"featuresFolderId");
+    assert!(every_prop_found(&tree));
+
+    let flat = FlatCharacter::from_tree_char(tree.clone());
+    assert_eq!(flat.creature_properties.iter().map(|p|p.id.as_str()).collect::<Vec<_>>(),
+        vec!["strengthId","featuresFolderId","actionId","branchId","noteId","itemId","toggleId","multiplierId"]);
+    assert!(in_order(&flat));
+    assert!(flat.creature_properties.iter().all(|p|p.ancestors.last()==Some(&p.parent)));
+
+    // and back out to the end of the roots
+    tree.move_node("actionId",None,usize::MAX).unwrap();
+    assert_eq!(tree.roots().last().unwrap().id,"actionId");
+    assert_eq!(tree.get("actionId").unwrap().ancestors.len(),1);
+    assert_eq!(tree.get("branchId").unwrap().ancestors.len(),2);
+    assert!(every_prop_found(&tree));
+}
+/// Whether `order` goes up along the flattened tree, the way dicecloud sorts it
+fn in_order(flat: &FlatCharacter)->bool{
+    flat.creature_properties.windows(2).all(|pair|pair[0].order<pair[1].order)
+}
+#[test]
+fn move_node_renumbers_in_tree_order(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    tree.move_node("multiplierId",Some("featuresFolderId"),0).unwrap();
+    let flat = FlatCharacter::from_tree_char(tree.clone());
+    assert!(in_order(&flat));
+    // the properties ahead of the move are where they were
+    assert_eq!((tree.get("strengthId").unwrap().order,tree.get("featuresFolderId").unwrap().order),(0,1));
+    let read = TreeCharacter::build_tree(flat);
+    assert_eq!(ids(read.get("featuresFolderId").unwrap().children()),vec!["multiplierId","noteId"]);
+}
+#[test]
+fn move_node_rejects_bad_moves(){
+    let mut tree = TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap());
+    assert!(matches!(tree.move_node("actionId",Some("branchId"),0),Err(Error::CyclicMove{..})));
+    assert!(matches!(tree.move_node("actionId",Some("actionId"),0),Err(Error::CyclicMove{..})));
+    assert!(matches!(tree.move_node("dne",None,0),Err(Error::NotFound{id}) if id=="dne"));
+    assert!(matches!(tree.move_node("actionId",Some("dne"),0),Err(Error::NotFound{..})));
+    assert_eq!(tree,TreeCharacter::build_tree(FlatCharacter::from_json(CHARACTER).unwrap()));
+}
@@ -0,0 +1,43 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{ExtraTag, ExtraTagOperation, FlatCharacter, TriggerEvent, TriggerTiming};
+use serde_json::json;
+
+mod common;
+use common::trigger;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+fn firing<'a>(character: &'a FlatCharacter, event: &TriggerEvent, timing: &TriggerTiming, property: Option<&str>)->Vec<&'a str>{
+    let property = property.map(|id|character.creature_properties.iter().find(|p|p.id==id).unwrap());
+    character.triggers_for(event,timing,property).into_iter().map(|p|p.id.as_str()).collect()
+}
+#[test]
+fn rests(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    character.creature_properties.extend([trigger("anyRestId","anyRest","after",&json!({})),trigger("longRestId","longRest","after",&json!({})),
+        trigger("beforeId","longRest","before",&json!({})),trigger("offId","longRest","after",&json!({"inactive": true}))]);
+    assert_eq!(firing(&character,&TriggerEvent::LongRest,&TriggerTiming::After,None),vec!["anyRestId","longRestId"]);
+    assert_eq!(firing(&character,&TriggerEvent::ShortRest,&TriggerTiming::After,None),vec!["anyRestId"]);
+    assert_eq!(firing(&character,&TriggerEvent::LongRest,&TriggerTiming::Before,None),vec!["beforeId"]);
+    assert_eq!(firing(&character,&TriggerEvent::AnyRest,&TriggerTiming::After,None),vec!["anyRestId"]);
+}
+#[test]
+fn action_properties(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    let or = ExtraTag{id: "orId".to_string(), operation: ExtraTagOperation::Or, tags: vec!["gear".to_string()], ..ExtraTag::default()};
+    let not = ExtraTag{id: "notId".to_string(), operation: ExtraTagOperation::Not, tags: vec!["weapon".to_string()], ..ExtraTag::default()};
+    character.creature_properties.extend([
+        trigger("anyId","doActionProperty","before",&json!({})),
+        trigger("weaponId","doActionProperty","before",&json!({"targetTags": ["weapon"], "actionPropertyType": "action"})),
+        trigger("itemOnlyId","doActionProperty","before",&json!({"actionPropertyType": "item"})),
+        trigger("orId","doActionProperty","before",&json!({"targetTags": ["magic"], "extraTags": [or]})),
+        trigger("notId","doActionProperty","before",&json!({"extraTags": [not]})),
+        trigger("checkId","check","before",&json!({}))]);
+    let event = TriggerEvent::DoActionProperty;
+    assert_eq!(firing(&character,&event,&TriggerTiming::Before,Some("actionId")),vec!["anyId","weaponId"]);
+    assert_eq!(firing(&character,&event,&TriggerTiming::Before,Some("itemId")),vec!["anyId","itemOnlyId","orId","notId"]);
+    assert!(firing(&character,&event,&TriggerTiming::Before,None).is_empty());
+    assert_eq!(firing(&character,&TriggerEvent::Check,&TriggerTiming::Before,Some("strengthId")),vec!["checkId"]);
+    let written = serde_json::to_value(character.creature_properties.last().unwrap()).unwrap();
+    assert_eq!((written["event"].as_str(),written["timing"].as_str()),(Some("check"),Some("before")));
+}
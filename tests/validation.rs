@@ -0,0 +1,54 @@
+#![cfg(feature="serde_json")]
+use dicecloud_models::{Collection, FlatCharacter, Finding};
+
+mod common;
+use common::with_prop;
+
+const CHARACTER: &str = include_str!("data/character.json");
+
+#[test]
+fn clean_character_has_no_findings(){
+    assert_eq!(FlatCharacter::from_json(CHARACTER).unwrap().validate(),vec![]);
+}
+#[test]
+fn parent_and_missing_ancestors(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    with_prop(&mut character,"noteId",|p|p.parent.id="actionId".to_string());
+    with_prop(&mut character,"branchId",|p|{
+        p.ancestors[1].id="gone".to_string();
+        p.parent.id="gone".to_string();
+    });
+    let findings = character.validate();
+    assert_eq!(findings,vec![
+        Finding::ParentMismatch{id: "noteId".to_string(), parent: "actionId".to_string(), last_ancestor: Some("featuresFolderId".to_string())},
+        Finding::MissingAncestor{id: "branchId".to_string(), missing: "gone".to_string()},
+    ]);
+    assert_eq!(findings[1].ids(),vec!["branchId"]);
+}
+#[test]
+fn order_collisions_and_collections(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    with_prop(&mut character,"toggleId",|p|p.order=3);
+    with_prop(&mut character,"itemId",|p|p.ancestors[0].collection=Collection::CreatureProperties);
+    let findings = character.validate();
+    assert!(findings.contains(&Finding::OrderCollision{order: 3, ids: vec!["actionId".to_string(),"toggleId".to_string()]}));
+    assert!(findings.contains(&Finding::WrongCollection{id: "itemId".to_string(), target: "creatureId1".to_string(),
+        collection: Collection::CreatureProperties, expected: Collection::Creatures}));
+    assert_eq!(findings.len(),2);
+}
+#[test]
+fn cycles(){
+    let mut character = FlatCharacter::from_json(CHARACTER).unwrap();
+    // the folder claims to be under the note, which is under the folder
+    let note = character.creature_properties.iter().find(|p|p.id=="noteId").unwrap().parent.clone();
+    let mut note_id = note.clone();
+    note_id.id = "noteId".to_string();
+    with_prop(&mut character,"featuresFolderId",|p|{
+        p.parent = note_id.clone();
+        p.ancestors.push(note_id);
+    });
+    let findings = character.validate();
+    assert!(findings.contains(&Finding::Cycle{ids: vec!["featuresFolderId".to_string(),"noteId".to_string()]}));
+    assert_eq!(findings.iter().filter(|f|matches!(f,Finding::Cycle{..})).count(),1);
+    assert!(findings.iter().all(|f|!f.to_string().is_empty()));
+}
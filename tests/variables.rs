@@ -0,0 +1,18 @@
+#![cfg(feature="serde_json")]
+use std::collections::HashMap;
+use dicecloud_models::CharacterVar;
+
+#[test]
+fn variables_are_not_read_as_empty(){
+    let vars: HashMap<String,CharacterVar> = serde_json::from_str("{
+        \"_id\": \"vars\",
+        \"strength\": {\"_id\": \"a\",\"variableName\": \"strength\",\"name\": \"Strength\",\"order\": 1,
+            \"type\": \"attribute\",\"total\": 14,\"value\": 14},
+        \"dne\": {},
+        \"counter\": {\"v\": 3}
+    }").unwrap();
+    assert!(matches!(vars["strength"],CharacterVar::Var(_)),"{:?}",vars["strength"]);
+    assert_eq!(vars["_id"],CharacterVar::Str("vars".to_string()));
+    assert!(matches!(&vars["dne"],CharacterVar::Empty{v,..} if v.is_none()),"{:?}",vars["dne"]);
+    assert!(matches!(&vars["counter"],CharacterVar::Empty{v,..} if **v==Some(3)),"{:?}",vars["counter"]);
+}